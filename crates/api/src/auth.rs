@@ -0,0 +1,75 @@
+use axum::{
+    Json,
+    extract::FromRequestParts,
+    http::{StatusCode, request::Parts},
+    response::{IntoResponse, Response},
+};
+use axum_extra::{
+    TypedHeader,
+    headers::{Authorization, authorization::Bearer},
+};
+use uuid::Uuid;
+
+use crate::state::AppState;
+
+/// A user authenticated via `Authorization: Bearer <api_key>`. Only the
+/// SHA-256 of each key is stored, so keys are compared by hash.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AuthUser {
+    pub id: Uuid,
+    pub is_admin: bool,
+}
+
+/// An authenticated user with `users.is_admin` set, for endpoints that change
+/// what every user sees.
+#[derive(Debug, Clone)]
+pub struct AdminUser(pub AuthUser);
+
+impl FromRequestParts<AppState> for AdminUser {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let user = AuthUser::from_request_parts(parts, state).await?;
+        if !user.is_admin {
+            return Err((StatusCode::FORBIDDEN, Json(serde_json::json!({
+                "error": "Admin access required"
+            }))).into_response());
+        }
+        Ok(AdminUser(user))
+    }
+}
+
+impl FromRequestParts<AppState> for AuthUser {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let TypedHeader(Authorization(bearer)) =
+            TypedHeader::<Authorization<Bearer>>::from_request_parts(parts, state)
+                .await
+                .map_err(|_| unauthorized("Missing or malformed Authorization header"))?;
+
+        let user = sqlx::query_as::<_, AuthUser>(
+            "SELECT id, is_admin FROM users WHERE api_key_hash = sha256(convert_to($1, 'UTF8'))"
+        )
+        .bind(bearer.token())
+        .fetch_optional(&state.db)
+        .await;
+
+        match user {
+            Ok(Some(user)) => Ok(user),
+            Ok(None) => Err(unauthorized("Invalid API key")),
+            Err(e) => {
+                tracing::error!("Failed to authenticate user: {}", e);
+                Err((StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+                    "error": "Failed to authenticate"
+                }))).into_response())
+            }
+        }
+    }
+}
+
+fn unauthorized(message: &str) -> Response {
+    (StatusCode::UNAUTHORIZED, Json(serde_json::json!({
+        "error": message
+    }))).into_response()
+}
@@ -15,9 +15,9 @@ pub async fn get<T: DeserializeOwned>(redis: &ConnectionManager, key: &str) -> O
 /// Cache a serializable value in Redis with a TTL in seconds.
 pub async fn set<T: Serialize>(redis: &ConnectionManager, key: &str, value: &T, ttl_secs: u64) {
     let mut conn = redis.clone();
-    if let Ok(json) = serde_json::to_string(value) {
-        if let Err(e) = conn.set_ex::<_, _, ()>(key, json, ttl_secs).await {
-            warn!("Redis cache set failed for key {}: {}", key, e);
-        }
+    if let Ok(json) = serde_json::to_string(value)
+        && let Err(e) = conn.set_ex::<_, _, ()>(key, json, ttl_secs).await
+    {
+        warn!("Redis cache set failed for key {}: {}", key, e);
     }
 }
@@ -1,59 +1,284 @@
 use axum::{
     Json, Router,
-    extract::{Query, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
     routing::get,
 };
-use serde::Deserialize;
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
 use crate::state::AppState;
+use forecise_consensus::calibration::{self, CalibrationReport};
+use forecise_consensus::comparison::{self, PairedComparison};
 use forecise_shared::models::*;
 
+/// Slug under which the consensus is listed next to the sources.
+const CONSENSUS_SLUG: &str = "forecise-consensus";
+
+/// Values of `prediction_scores.horizon`. The consensus is not time-averaged.
+const SCORE_HORIZONS: [&str; 6] = ["final", "time_avg", "1d", "7d", "30d", "90d"];
+
+fn invalid_horizon() -> axum::response::Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({
+            "error": "Invalid horizon. Use final, time_avg, 1d, 7d, 30d or 90d"
+        })),
+    )
+        .into_response()
+}
+
 pub fn routes() -> Router<AppState> {
-    Router::new().route("/leaderboard", get(leaderboard))
+    Router::new()
+        .route("/leaderboard", get(leaderboard))
+        .route("/leaderboard/users", get(user_leaderboard))
+        .route("/leaderboard/teams", get(team_leaderboard))
+        .route("/consensus", get(consensus_comparison))
+        .route("/compare", get(compare_sources))
+        .route("/{source}/calibration", get(source_calibration))
+        .route("/{source}/history", get(source_history))
 }
 
 #[derive(Debug, Deserialize)]
 pub struct LeaderboardParams {
     pub category: Option<String>,
     pub limit: Option<i64>,
+    /// `skill` (default), `climatology_skill`, `brier`, `log_score`, `spherical` or `accuracy`.
+    pub sort: Option<String>,
+    /// When forecasts are scored: `final` (default), `time_avg`, `1d`, `7d`, `30d` or `90d`.
+    pub horizon: Option<String>,
+    /// Minimum scored markets to be ranked (default 30).
+    pub min_resolved: Option<i64>,
+    /// Only markets resolved in the last this many days.
+    pub days: Option<i32>,
 }
 
+/// Confidence level of the leaderboard intervals.
+const LEADERBOARD_LEVEL: f64 = 0.95;
+
+/// SQL for a leaderboard sort key over the per-source `stats` CTE: its value,
+/// its standard error, and whether higher is better.
+fn leaderboard_metric(sort: Option<&str>) -> Option<(&'static str, &'static str, bool)> {
+    Some(match sort.unwrap_or("skill") {
+        "accuracy" => ("(1 - brier) * 100", "brier_sd / SQRT(n) * 100", true),
+        "brier" => ("brier", "brier_sd / SQRT(n)", false),
+        "log_score" => ("log", "log_sd / SQRT(n)", true),
+        "spherical" => ("spherical", "spherical_sd / SQRT(n)", true),
+        "skill" => ("1 - brier / 0.25", "brier_sd / SQRT(n) / 0.25", true),
+        "climatology_skill" => (
+            "1 - brier / NULLIF(climatology, 0)",
+            "brier_sd / SQRT(n) / NULLIF(climatology, 0)",
+            true,
+        ),
+        _ => return None,
+    })
+}
+
+/// Sources and the consensus ranked by a proper score with confidence
+/// intervals. Entries whose intervals overlap share a rank.
 async fn leaderboard(
     State(state): State<AppState>,
     Query(params): Query<LeaderboardParams>,
 ) -> impl IntoResponse {
-    let limit = params.limit.unwrap_or(20).min(100);
+    let limit = params.limit.unwrap_or(20).clamp(1, 100) as usize;
+    let min_resolved = params.min_resolved.unwrap_or(30).max(2);
+    let Some((value, std_error, higher_is_better)) = leaderboard_metric(params.sort.as_deref()) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "Invalid sort. Use skill, climatology_skill, brier, log_score, spherical or accuracy"
+            })),
+        )
+            .into_response();
+    };
+    let horizon = params.horizon.as_deref().unwrap_or("final");
+    if !SCORE_HORIZONS.contains(&horizon) {
+        return invalid_horizon();
+    }
 
     // Try Redis cache
     let cache_key = format!(
-        "accuracy:leaderboard:{}:{}",
+        "accuracy:leaderboard:{}:{}:{}:{}:{}:{}",
         params.category.as_deref().unwrap_or(""),
+        limit,
+        params.sort.as_deref().unwrap_or("skill"),
+        horizon,
+        min_resolved,
+        params.days.unwrap_or_default()
+    );
+    if let Some(cached) = crate::cache::get::<serde_json::Value>(&state.redis, &cache_key).await {
+        return Json(cached).into_response();
+    }
+
+    // Sources plus the consensus itself, aggregated from individual scores so
+    // the window and minimum apply to both
+    let direction = if higher_is_better { "DESC" } else { "ASC" };
+    let query = format!(
+        r#"
+        WITH scores AS (
+            SELECT
+                s.name as source_name,
+                s.slug as source_slug,
+                false as is_consensus,
+                ps.brier_score,
+                ps.log_score,
+                ps.spherical_score,
+                ps.actual_outcome
+            FROM prediction_scores ps
+            JOIN sources s ON ps.source_id = s.id
+            WHERE ps.horizon = $4
+            AND ($1::text IS NULL OR ps.category_id = (SELECT id FROM categories WHERE slug = $1))
+            AND ($5::int IS NULL OR ps.resolved_at >= NOW() - make_interval(days => $5::int))
+            UNION ALL
+            SELECT 'Forecise Consensus', $3, true, cs.brier_score, cs.log_score, cs.spherical_score, cs.actual_outcome
+            FROM consensus_scores cs
+            WHERE cs.horizon = $4
+            AND ($1::text IS NULL OR cs.category_id = (SELECT id FROM categories WHERE slug = $1))
+            AND ($5::int IS NULL OR cs.resolved_at >= NOW() - make_interval(days => $5::int))
+        ),
+        stats AS (
+            SELECT
+                source_name,
+                source_slug,
+                is_consensus,
+                COUNT(*) as n,
+                AVG(brier_score) as brier,
+                STDDEV_SAMP(brier_score) as brier_sd,
+                AVG(log_score) as log,
+                STDDEV_SAMP(log_score) as log_sd,
+                AVG(spherical_score) as spherical,
+                STDDEV_SAMP(spherical_score) as spherical_sd,
+                AVG(actual_outcome) * (1 - AVG(actual_outcome)) as climatology
+            FROM scores
+            GROUP BY source_name, source_slug, is_consensus
+            HAVING COUNT(*) >= $2
+        )
+        SELECT
+            ROW_NUMBER() OVER (ORDER BY {value} {direction} NULLS LAST) as rank,
+            source_name,
+            source_slug,
+            ((1 - brier) * 100)::DECIMAL(10, 4) as accuracy_pct,
+            brier::DECIMAL(10, 6) as brier_score,
+            log::DECIMAL(10, 6) as log_score,
+            spherical::DECIMAL(10, 6) as spherical_score,
+            (1 - brier / 0.25)::DECIMAL(10, 6) as brier_skill_score,
+            (1 - brier / NULLIF(climatology, 0))::DECIMAL(10, 6) as climatology_skill_score,
+            n::INTEGER as total_resolved,
+            is_consensus,
+            ({value})::float8 as score,
+            ({std_error})::float8 as std_error,
+            NULL::float8 as ci_lower,
+            NULL::float8 as ci_upper,
+            false as tied
+        FROM stats
+        ORDER BY {value} {direction} NULLS LAST
+        "#
+    );
+    let entries = sqlx::query_as::<_, AccuracyLeaderboardEntry>(&query)
+        .bind(&params.category)
+        .bind(min_resolved)
+        .bind(CONSENSUS_SLUG)
+        .bind(horizon)
+        .bind(params.days)
+        .fetch_all(&state.db)
+        .await;
+
+    match entries {
+        Ok(mut data) => {
+            for entry in &mut data {
+                if let (Some(score), Some(se)) = (entry.score, entry.std_error) {
+                    let half_width = comparison::t_critical(LEADERBOARD_LEVEL, entry.total_resolved as f64 - 1.0) * se;
+                    entry.ci_lower = Some(score - half_width);
+                    entry.ci_upper = Some(score + half_width);
+                }
+            }
+            let intervals: Vec<(f64, f64)> = data.iter()
+                .map(|e| match (e.ci_lower, e.ci_upper) {
+                    (Some(lo), Some(hi)) => (lo, hi),
+                    // No score: ranked below everything
+                    _ if higher_is_better => (f64::NEG_INFINITY, f64::NEG_INFINITY),
+                    _ => (f64::INFINITY, f64::INFINITY),
+                })
+                .collect();
+            for (entry, (rank, tied)) in data.iter_mut().zip(comparison::rank_with_ties(&intervals, higher_is_better)) {
+                entry.rank = rank as i64;
+                entry.tied = tied;
+            }
+            // Interval ranks need not follow the point scores, so order by
+            // rank first and by score within a rank
+            data.sort_by(|a, b| {
+                let by_score = a.score.partial_cmp(&b.score).unwrap_or(std::cmp::Ordering::Equal);
+                a.rank.cmp(&b.rank).then(if higher_is_better { by_score.reverse() } else { by_score })
+            });
+            data.truncate(limit);
+
+            let response = ApiResponse::new(data);
+            crate::cache::set(&state.redis, &cache_key, &response, 60).await;
+            Json(response).into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to get leaderboard: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": "Failed to fetch leaderboard"
+                })),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ForecasterLeaderboardParams {
+    pub category: Option<String>,
+    pub min_resolved: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+async fn user_leaderboard(
+    State(state): State<AppState>,
+    Query(params): Query<ForecasterLeaderboardParams>,
+) -> impl IntoResponse {
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let min_resolved = params.min_resolved.unwrap_or(10).max(1);
+
+    let cache_key = format!(
+        "accuracy:leaderboard:users:{}:{}:{}",
+        params.category.as_deref().unwrap_or(""),
+        min_resolved,
         limit
     );
     if let Some(cached) = crate::cache::get::<serde_json::Value>(&state.redis, &cache_key).await {
         return Json(cached).into_response();
     }
 
-    let entries = sqlx::query_as::<_, AccuracyLeaderboardEntry>(
+    let entries = sqlx::query_as::<_, UserLeaderboardEntry>(
         r#"
         SELECT
-            ROW_NUMBER() OVER (ORDER BY ar.accuracy_pct DESC NULLS LAST) as rank,
-            s.name as source_name,
-            s.slug as source_slug,
-            ar.accuracy_pct,
-            ar.brier_score,
-            ar.total_resolved
-        FROM accuracy_records ar
-        JOIN sources s ON ar.source_id = s.id
-        WHERE ar.total_resolved >= 30
-        AND ($1::text IS NULL OR ar.category_id = (SELECT id FROM categories WHERE slug = $1))
-        ORDER BY ar.accuracy_pct DESC NULLS LAST
-        LIMIT $2
+            ROW_NUMBER() OVER (ORDER BY AVG(ups.brier_score) ASC) as rank,
+            u.id as user_id,
+            u.name as user_name,
+            t.slug as team_slug,
+            ((1 - AVG(ups.brier_score)) * 100)::DECIMAL(10, 4) as accuracy_pct,
+            AVG(ups.brier_score)::DECIMAL(10, 6) as brier_score,
+            (1 - AVG(ups.brier_score) / 0.25)::DECIMAL(10, 6) as brier_skill_score,
+            (1 - AVG(ups.brier_score) / NULLIF(AVG(ups.actual_outcome) * (1 - AVG(ups.actual_outcome)), 0))::DECIMAL(10, 6) as climatology_skill_score,
+            COUNT(*) as total_resolved
+        FROM user_prediction_scores ups
+        JOIN users u ON ups.user_id = u.id
+        LEFT JOIN teams t ON u.team_id = t.id
+        WHERE ($1::text IS NULL OR ups.category_id = (SELECT id FROM categories WHERE slug = $1))
+        GROUP BY u.id, u.name, t.slug
+        HAVING COUNT(*) >= $2
+        ORDER BY AVG(ups.brier_score) ASC
+        LIMIT $3
         "#,
     )
     .bind(&params.category)
+    .bind(min_resolved)
     .bind(limit)
     .fetch_all(&state.db)
     .await;
@@ -65,11 +290,674 @@ async fn leaderboard(
             Json(response).into_response()
         }
         Err(e) => {
-            tracing::error!("Failed to get leaderboard: {}", e);
+            tracing::error!("Failed to get user leaderboard: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(serde_json::json!({
-                    "error": "Failed to fetch leaderboard"
+                    "error": "Failed to fetch user leaderboard"
+                })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Teams are scored on the pooled forecasts of all their members.
+async fn team_leaderboard(
+    State(state): State<AppState>,
+    Query(params): Query<ForecasterLeaderboardParams>,
+) -> impl IntoResponse {
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let min_resolved = params.min_resolved.unwrap_or(10).max(1);
+
+    let cache_key = format!(
+        "accuracy:leaderboard:teams:{}:{}:{}",
+        params.category.as_deref().unwrap_or(""),
+        min_resolved,
+        limit
+    );
+    if let Some(cached) = crate::cache::get::<serde_json::Value>(&state.redis, &cache_key).await {
+        return Json(cached).into_response();
+    }
+
+    let entries = sqlx::query_as::<_, TeamLeaderboardEntry>(
+        r#"
+        SELECT
+            ROW_NUMBER() OVER (ORDER BY AVG(ups.brier_score) ASC) as rank,
+            t.name as team_name,
+            t.slug as team_slug,
+            COUNT(DISTINCT u.id) as member_count,
+            ((1 - AVG(ups.brier_score)) * 100)::DECIMAL(10, 4) as accuracy_pct,
+            AVG(ups.brier_score)::DECIMAL(10, 6) as brier_score,
+            (1 - AVG(ups.brier_score) / 0.25)::DECIMAL(10, 6) as brier_skill_score,
+            (1 - AVG(ups.brier_score) / NULLIF(AVG(ups.actual_outcome) * (1 - AVG(ups.actual_outcome)), 0))::DECIMAL(10, 6) as climatology_skill_score,
+            COUNT(*) as total_resolved
+        FROM user_prediction_scores ups
+        JOIN users u ON ups.user_id = u.id
+        JOIN teams t ON u.team_id = t.id
+        WHERE ($1::text IS NULL OR ups.category_id = (SELECT id FROM categories WHERE slug = $1))
+        GROUP BY t.id, t.name, t.slug
+        HAVING COUNT(*) >= $2
+        ORDER BY AVG(ups.brier_score) ASC
+        LIMIT $3
+        "#,
+    )
+    .bind(&params.category)
+    .bind(min_resolved)
+    .bind(limit)
+    .fetch_all(&state.db)
+    .await;
+
+    match entries {
+        Ok(data) => {
+            let response = ApiResponse::new(data);
+            crate::cache::set(&state.redis, &cache_key, &response, 60).await;
+            Json(response).into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to get team leaderboard: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": "Failed to fetch team leaderboard"
+                })),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConsensusComparisonParams {
+    /// Minimum markets for a source to be considered the best source.
+    pub min_resolved: Option<i64>,
+}
+
+/// The consensus against its sources overall and per category, on the markets
+/// where the consensus was scored.
+async fn consensus_comparison(
+    State(state): State<AppState>,
+    Query(params): Query<ConsensusComparisonParams>,
+) -> impl IntoResponse {
+    let min_resolved = params.min_resolved.unwrap_or(10).max(1);
+
+    let cache_key = format!("accuracy:consensus:{}", min_resolved);
+    if let Some(cached) = crate::cache::get::<serde_json::Value>(&state.redis, &cache_key).await {
+        return Json(cached).into_response();
+    }
+
+    let entries = sqlx::query_as::<_, ConsensusComparisonEntry>(
+        r#"
+        WITH groups AS (
+            SELECT NULL::uuid as category_id, NULL::text as slug, NULL::text as name
+            UNION ALL
+            SELECT id, slug, name FROM categories
+        ),
+        final AS (
+            SELECT * FROM consensus_scores WHERE horizon = 'final'
+        )
+        SELECT
+            g.slug as category_slug,
+            g.name as category_name,
+            COUNT(f.id) as markets,
+            AVG(f.brier_score)::DECIMAL(10, 6) as consensus_brier,
+            AVG(f.extremized_brier_score)::DECIMAL(10, 6) as extremized_brier,
+            (
+                SELECT AVG(ps.brier_score)
+                FROM prediction_scores ps
+                JOIN final f2 ON f2.market_id = ps.market_id
+                WHERE ps.horizon = 'final'
+                AND (g.category_id IS NULL OR f2.category_id = g.category_id)
+            )::DECIMAL(10, 6) as mean_source_brier,
+            best.slug as best_source_slug,
+            best.brier as best_source_brier,
+            (
+                SELECT AVG(c.brier_score) FROM consensus_scores c
+                WHERE c.horizon = '1d' AND (g.category_id IS NULL OR c.category_id = g.category_id)
+            )::DECIMAL(10, 6) as consensus_brier_1d,
+            (
+                SELECT AVG(c.brier_score) FROM consensus_scores c
+                WHERE c.horizon = '7d' AND (g.category_id IS NULL OR c.category_id = g.category_id)
+            )::DECIMAL(10, 6) as consensus_brier_7d,
+            (
+                SELECT AVG(c.brier_score) FROM consensus_scores c
+                WHERE c.horizon = '30d' AND (g.category_id IS NULL OR c.category_id = g.category_id)
+            )::DECIMAL(10, 6) as consensus_brier_30d
+        FROM groups g
+        JOIN final f ON g.category_id IS NULL OR f.category_id = g.category_id
+        LEFT JOIN LATERAL (
+            SELECT s.slug, AVG(ps.brier_score)::DECIMAL(10, 6) as brier
+            FROM prediction_scores ps
+            JOIN final f3 ON f3.market_id = ps.market_id
+            JOIN sources s ON ps.source_id = s.id
+            WHERE ps.horizon = 'final'
+            AND (g.category_id IS NULL OR f3.category_id = g.category_id)
+            GROUP BY s.slug
+            HAVING COUNT(*) >= $1
+            ORDER BY AVG(ps.brier_score) ASC
+            LIMIT 1
+        ) best ON true
+        GROUP BY g.category_id, g.slug, g.name, best.slug, best.brier
+        ORDER BY g.slug NULLS FIRST
+        "#,
+    )
+    .bind(min_resolved)
+    .fetch_all(&state.db)
+    .await;
+
+    match entries {
+        Ok(data) => {
+            let response = ApiResponse::new(data);
+            crate::cache::set(&state.redis, &cache_key, &response, 300).await;
+            Json(response).into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to get consensus comparison: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": "Failed to fetch consensus comparison"
+                })),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CalibrationParams {
+    pub category: Option<String>,
+    /// Only predictions on markets resolved at or after this time.
+    pub from: Option<DateTime<Utc>>,
+    /// Only predictions on markets resolved before this time.
+    pub to: Option<DateTime<Utc>>,
+    pub bins: Option<usize>,
+    /// Scoring horizon, `final` by default.
+    pub horizon: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SourceCalibration {
+    pub source_slug: String,
+    pub source_name: String,
+    pub category: Option<String>,
+    /// `None` without resolved predictions in the window.
+    pub calibration: Option<CalibrationReport>,
+}
+
+/// Reliability diagram and Murphy decomposition of a source's resolved
+/// predictions. `forecise-consensus` scores the consensus' snapshots.
+async fn source_calibration(
+    State(state): State<AppState>,
+    Path(source): Path<String>,
+    Query(params): Query<CalibrationParams>,
+) -> impl IntoResponse {
+    let bins = params.bins.unwrap_or(calibration::DEFAULT_BINS).clamp(2, 50);
+    let horizon = params.horizon.clone().unwrap_or_else(|| "final".to_string());
+    if !SCORE_HORIZONS.contains(&horizon.as_str()) {
+        return invalid_horizon();
+    }
+
+    let cache_key = format!(
+        "accuracy:calibration:{}:{}:{}:{}:{}:{}",
+        source,
+        horizon,
+        params.category.as_deref().unwrap_or(""),
+        params.from.map(|t| t.timestamp()).unwrap_or_default(),
+        params.to.map(|t| t.timestamp()).unwrap_or_default(),
+        bins
+    );
+    if let Some(cached) = crate::cache::get::<serde_json::Value>(&state.redis, &cache_key).await {
+        return Json(cached).into_response();
+    }
+
+    let result = if source == CONSENSUS_SLUG {
+        consensus_predictions(&state, &horizon, &params)
+            .await
+            .map(|rows| Some(("Forecise Consensus".to_string(), rows)))
+    } else {
+        source_predictions(&state, &source, &horizon, &params).await
+    };
+
+    match result {
+        Ok(Some((source_name, rows))) => {
+            let forecasts: Vec<(f64, f64)> = rows.iter()
+                .map(|(p, y)| (
+                    p.to_string().parse().unwrap_or(0.5),
+                    y.to_string().parse().unwrap_or(0.0),
+                ))
+                .collect();
+            let response = ApiResponse::new(SourceCalibration {
+                source_slug: source,
+                source_name,
+                category: params.category,
+                calibration: calibration::calibration_report(&forecasts, bins),
+            });
+            crate::cache::set(&state.redis, &cache_key, &response, 300).await;
+            Json(response).into_response()
+        }
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": "Source not found"
+            })),
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::error!("Failed to get calibration for {}: {}", source, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": "Failed to fetch calibration"
+                })),
+            )
+                .into_response()
+        }
+    }
+}
+
+type PredictionRows = Vec<(BigDecimal, BigDecimal)>;
+
+/// The source's name and its `(predicted, outcome)` pairs, or `None` for an unknown slug.
+async fn source_predictions(
+    state: &AppState,
+    slug: &str,
+    horizon: &str,
+    params: &CalibrationParams,
+) -> Result<Option<(String, PredictionRows)>, sqlx::Error> {
+    let Some(name) = sqlx::query_scalar::<_, String>("SELECT name FROM sources WHERE slug = $1")
+        .bind(slug)
+        .fetch_optional(&state.db)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    let rows = sqlx::query_as::<_, (BigDecimal, BigDecimal)>(
+        r#"
+        SELECT ps.predicted_probability, ps.actual_outcome
+        FROM prediction_scores ps
+        JOIN sources s ON ps.source_id = s.id
+        WHERE s.slug = $1
+        AND ps.horizon = $5
+        AND ($2::text IS NULL OR ps.category_id = (SELECT id FROM categories WHERE slug = $2))
+        AND ($3::timestamptz IS NULL OR ps.resolved_at >= $3)
+        AND ($4::timestamptz IS NULL OR ps.resolved_at < $4)
+        "#,
+    )
+    .bind(slug)
+    .bind(&params.category)
+    .bind(params.from)
+    .bind(params.to)
+    .bind(horizon)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Some((name, rows)))
+}
+
+async fn consensus_predictions(
+    state: &AppState,
+    horizon: &str,
+    params: &CalibrationParams,
+) -> Result<PredictionRows, sqlx::Error> {
+    sqlx::query_as::<_, (BigDecimal, BigDecimal)>(
+        r#"
+        SELECT cs.predicted_probability, cs.actual_outcome
+        FROM consensus_scores cs
+        WHERE cs.horizon = $4
+        AND ($1::text IS NULL OR cs.category_id = (SELECT id FROM categories WHERE slug = $1))
+        AND ($2::timestamptz IS NULL OR cs.resolved_at >= $2)
+        AND ($3::timestamptz IS NULL OR cs.resolved_at < $3)
+        "#,
+    )
+    .bind(&params.category)
+    .bind(params.from)
+    .bind(params.to)
+    .bind(horizon)
+    .fetch_all(&state.db)
+    .await
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CompareParams {
+    pub a: String,
+    pub b: String,
+    /// `brier` (default), `log_score` or `spherical`.
+    pub score: Option<String>,
+    /// Scoring horizon, `final` by default.
+    pub horizon: Option<String>,
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategoryComparison {
+    pub category_slug: Option<String>,
+    pub comparison: PairedComparison,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SourceComparison {
+    pub a: String,
+    pub b: String,
+    pub score: String,
+    pub horizon: String,
+    /// Whether a lower score is better (Brier); `mean_difference` is `a - b`.
+    pub lower_is_better: bool,
+    /// `None` with fewer than two shared markets.
+    pub overall: Option<PairedComparison>,
+    /// Categories with at least two shared markets.
+    pub by_category: Vec<CategoryComparison>,
+}
+
+/// Paired comparison of two sources (or `forecise-consensus`) on the markets
+/// both were scored on.
+async fn compare_sources(
+    State(state): State<AppState>,
+    Query(params): Query<CompareParams>,
+) -> impl IntoResponse {
+    let score = params.score.as_deref().unwrap_or("brier");
+    let (column, lower_is_better) = match score {
+        "brier" => ("brier_score", true),
+        "log_score" => ("log_score", false),
+        "spherical" => ("spherical_score", false),
+        _ => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": "Invalid score. Use brier, log_score or spherical"
+                })),
+            )
+                .into_response();
+        }
+    };
+    let horizon = params.horizon.as_deref().unwrap_or("final");
+    if !SCORE_HORIZONS.contains(&horizon) {
+        return invalid_horizon();
+    }
+    if params.a == params.b {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "Compare two different sources"
+            })),
+        )
+            .into_response();
+    }
+
+    let cache_key = format!(
+        "accuracy:compare:{}:{}:{}:{}:{}",
+        params.a,
+        params.b,
+        score,
+        horizon,
+        params.category.as_deref().unwrap_or("")
+    );
+    if let Some(cached) = crate::cache::get::<serde_json::Value>(&state.redis, &cache_key).await {
+        return Json(cached).into_response();
+    }
+
+    let known = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM sources WHERE slug = ANY($1)")
+        .bind(vec![params.a.clone(), params.b.clone()])
+        .fetch_one(&state.db)
+        .await;
+    let expected = [&params.a, &params.b].iter().filter(|s| **s != CONSENSUS_SLUG).count() as i64;
+    match known {
+        Ok(count) if count < expected => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({
+                    "error": "Source not found"
+                })),
+            )
+                .into_response();
+        }
+        Ok(_) => {}
+        Err(e) => {
+            tracing::error!("Failed to look up sources: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": "Failed to compare sources"
+                })),
+            )
+                .into_response();
+        }
+    }
+
+    // One score per source and market; a source listing a market twice is averaged
+    let query = format!(
+        r#"
+        WITH scores AS (
+            SELECT s.slug, ps.market_id, ps.category_id, ps.{column} as score
+            FROM prediction_scores ps
+            JOIN sources s ON ps.source_id = s.id
+            WHERE ps.horizon = $3
+            UNION ALL
+            SELECT $4, cs.market_id, cs.category_id, cs.{column}
+            FROM consensus_scores cs
+            WHERE cs.horizon = $3
+        ),
+        per_market AS (
+            SELECT slug, market_id, category_id, AVG(score) as score
+            FROM scores
+            WHERE slug IN ($1, $2)
+            GROUP BY slug, market_id, category_id
+        )
+        SELECT c.slug as category_slug, a.score as score_a, b.score as score_b
+        FROM per_market a
+        JOIN per_market b ON b.market_id = a.market_id AND b.slug = $2
+        LEFT JOIN categories c ON a.category_id = c.id
+        WHERE a.slug = $1
+        AND ($5::text IS NULL OR c.slug = $5)
+        "#
+    );
+    let rows = sqlx::query_as::<_, (Option<String>, BigDecimal, BigDecimal)>(&query)
+        .bind(&params.a)
+        .bind(&params.b)
+        .bind(horizon)
+        .bind(CONSENSUS_SLUG)
+        .bind(&params.category)
+        .fetch_all(&state.db)
+        .await;
+
+    match rows {
+        Ok(rows) => {
+            let mut pairs = Vec::with_capacity(rows.len());
+            let mut by_category: std::collections::BTreeMap<Option<String>, Vec<(f64, f64)>> =
+                std::collections::BTreeMap::new();
+            for (category, a, b) in rows {
+                let pair = (
+                    a.to_string().parse().unwrap_or(0.0),
+                    b.to_string().parse().unwrap_or(0.0),
+                );
+                pairs.push(pair);
+                by_category.entry(category).or_default().push(pair);
+            }
+            let level = comparison::DEFAULT_COMPARISON_LEVEL;
+
+            let response = ApiResponse::new(SourceComparison {
+                a: params.a,
+                b: params.b,
+                score: score.to_string(),
+                horizon: horizon.to_string(),
+                lower_is_better,
+                overall: comparison::compare_paired(&pairs, level),
+                by_category: by_category
+                    .into_iter()
+                    .filter_map(|(category_slug, pairs)| {
+                        comparison::compare_paired(&pairs, level)
+                            .map(|comparison| CategoryComparison { category_slug, comparison })
+                    })
+                    .collect(),
+            });
+            crate::cache::set(&state.redis, &cache_key, &response, 300).await;
+            Json(response).into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to compare sources: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": "Failed to compare sources"
+                })),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryParams {
+    /// `week` or `month` (default).
+    pub interval: Option<String>,
+    /// Length of the trailing window ending at each period (default 90).
+    pub window_days: Option<i32>,
+    /// Scoring horizon, `final` by default.
+    pub horizon: Option<String>,
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct AccuracyHistoryPoint {
+    pub period_start: DateTime<Utc>,
+    /// Markets resolved in the period and their mean scores.
+    pub resolved: i64,
+    pub brier_score: Option<BigDecimal>,
+    pub log_score: Option<BigDecimal>,
+    /// Markets resolved in the trailing window ending with the period, and
+    /// their mean Brier score.
+    pub rolling_resolved: i64,
+    pub rolling_brier_score: Option<BigDecimal>,
+}
+
+/// How a source's (or the consensus') score changed over time, per period
+/// and over a trailing window.
+async fn source_history(
+    State(state): State<AppState>,
+    Path(source): Path<String>,
+    Query(params): Query<HistoryParams>,
+) -> impl IntoResponse {
+    let interval = params.interval.as_deref().unwrap_or("month");
+    if !matches!(interval, "week" | "month") {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "Invalid interval. Use week or month"
+            })),
+        )
+            .into_response();
+    }
+    let window_days = params.window_days.unwrap_or(90).clamp(7, 3650);
+    let horizon = params.horizon.as_deref().unwrap_or("final");
+    if !SCORE_HORIZONS.contains(&horizon) {
+        return invalid_horizon();
+    }
+
+    let cache_key = format!(
+        "accuracy:history:{}:{}:{}:{}:{}",
+        source,
+        interval,
+        window_days,
+        horizon,
+        params.category.as_deref().unwrap_or("")
+    );
+    if let Some(cached) = crate::cache::get::<serde_json::Value>(&state.redis, &cache_key).await {
+        return Json(cached).into_response();
+    }
+
+    if source != CONSENSUS_SLUG {
+        match sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM sources WHERE slug = $1")
+            .bind(&source)
+            .fetch_one(&state.db)
+            .await
+        {
+            Ok(0) => {
+                return (
+                    StatusCode::NOT_FOUND,
+                    Json(serde_json::json!({
+                        "error": "Source not found"
+                    })),
+                )
+                    .into_response();
+            }
+            Ok(_) => {}
+            Err(e) => {
+                tracing::error!("Failed to look up source {}: {}", source, e);
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({
+                        "error": "Failed to fetch accuracy history"
+                    })),
+                )
+                    .into_response();
+            }
+        }
+    }
+
+    let points = sqlx::query_as::<_, AccuracyHistoryPoint>(
+        r#"
+        WITH scores AS (
+            SELECT ps.resolved_at, ps.brier_score, ps.log_score
+            FROM prediction_scores ps
+            JOIN sources s ON ps.source_id = s.id
+            WHERE s.slug = $1
+            AND ps.horizon = $2
+            AND ($5::text IS NULL OR ps.category_id = (SELECT id FROM categories WHERE slug = $5))
+            UNION ALL
+            SELECT cs.resolved_at, cs.brier_score, cs.log_score
+            FROM consensus_scores cs
+            WHERE $1 = $6
+            AND cs.horizon = $2
+            AND ($5::text IS NULL OR cs.category_id = (SELECT id FROM categories WHERE slug = $5))
+        ),
+        periods AS (
+            SELECT
+                date_trunc($3, resolved_at) as period_start,
+                COUNT(*) as resolved,
+                AVG(brier_score) as brier_score,
+                AVG(log_score) as log_score
+            FROM scores
+            GROUP BY 1
+        )
+        SELECT
+            p.period_start,
+            p.resolved,
+            p.brier_score::DECIMAL(10, 6) as brier_score,
+            p.log_score::DECIMAL(10, 6) as log_score,
+            r.resolved as rolling_resolved,
+            r.brier_score::DECIMAL(10, 6) as rolling_brier_score
+        FROM periods p
+        JOIN LATERAL (
+            SELECT COUNT(*) as resolved, AVG(s.brier_score) as brier_score
+            FROM scores s
+            WHERE s.resolved_at < p.period_start + ('1 ' || $3)::interval
+            AND s.resolved_at >= p.period_start + ('1 ' || $3)::interval - make_interval(days => $4)
+        ) r ON true
+        ORDER BY p.period_start
+        "#,
+    )
+    .bind(&source)
+    .bind(horizon)
+    .bind(interval)
+    .bind(window_days)
+    .bind(&params.category)
+    .bind(CONSENSUS_SLUG)
+    .fetch_all(&state.db)
+    .await;
+
+    match points {
+        Ok(data) => {
+            let response = ApiResponse::new(data);
+            crate::cache::set(&state.redis, &cache_key, &response, 300).await;
+            Json(response).into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to get accuracy history for {}: {}", source, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": "Failed to fetch accuracy history"
                 })),
             )
                 .into_response()
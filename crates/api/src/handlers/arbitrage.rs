@@ -0,0 +1,161 @@
+use axum::{
+    Router, Json,
+    extract::{Path, Query, State},
+    routing::get,
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+
+use forecise_shared::models::*;
+use crate::state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_open_arbitrage))
+        .route("/{market_id}", get(get_market_arbitrage))
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ArbitrageWithContext {
+    pub id: Uuid,
+    pub market_id: Uuid,
+    pub market_title: String,
+    pub category: Option<String>,
+    pub yes_source: String,
+    pub yes_url: Option<String>,
+    pub yes_price: BigDecimal,
+    pub no_source: String,
+    pub no_url: Option<String>,
+    pub no_price: BigDecimal,
+    pub fees: BigDecimal,
+    pub edge: BigDecimal,
+    pub return_pct: BigDecimal,
+    pub max_edge: BigDecimal,
+    pub executable: bool,
+    pub play_money: bool,
+    pub detected_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+const SELECT_WITH_CONTEXT: &str = r#"
+    SELECT
+        ao.id, ao.market_id,
+        m.title as market_title,
+        c.slug as category,
+        ys.name as yes_source, ysm.external_url as yes_url, ao.yes_price,
+        ns.name as no_source, nsm.external_url as no_url, ao.no_price,
+        ao.fees, ao.edge, ao.return_pct, ao.max_edge,
+        ao.executable, ao.play_money,
+        ao.detected_at, ao.last_seen_at, ao.closed_at
+    FROM arbitrage_opportunities ao
+    JOIN markets m ON ao.market_id = m.id
+    LEFT JOIN categories c ON m.category_id = c.id
+    JOIN source_markets ysm ON ao.yes_source_market_id = ysm.id
+    JOIN sources ys ON ysm.source_id = ys.id
+    JOIN source_markets nsm ON ao.no_source_market_id = nsm.id
+    JOIN sources ns ON nsm.source_id = ns.id
+"#;
+
+#[derive(Debug, Deserialize)]
+pub struct ArbitrageParams {
+    /// Minimum edge per unit of payout, after fees.
+    pub min_edge: Option<f64>,
+    pub category: Option<String>,
+    /// Only opportunities with both legs priced from a live bid/ask.
+    pub executable_only: Option<bool>,
+    /// Include opportunities between play-money venues.
+    pub include_play_money: Option<bool>,
+    pub limit: Option<i64>,
+}
+
+/// Open cross-venue opportunities, best edge first.
+async fn get_open_arbitrage(
+    State(state): State<AppState>,
+    Query(params): Query<ArbitrageParams>,
+) -> impl IntoResponse {
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    let min_edge = params.min_edge.unwrap_or(0.0);
+    let executable_only = params.executable_only.unwrap_or(false);
+    let include_play_money = params.include_play_money.unwrap_or(false);
+
+    let cache_key = format!(
+        "arbitrage:open:{}:{}:{}:{}:{}",
+        min_edge,
+        params.category.as_deref().unwrap_or(""),
+        executable_only,
+        include_play_money,
+        limit
+    );
+    if let Some(cached) = crate::cache::get::<serde_json::Value>(&state.redis, &cache_key).await {
+        return Json(cached).into_response();
+    }
+
+    let query = format!(
+        r#"
+        {SELECT_WITH_CONTEXT}
+        WHERE ao.closed_at IS NULL
+        AND ao.edge >= $1
+        AND ($2::text IS NULL OR c.slug = $2)
+        AND (NOT $3 OR ao.executable)
+        AND ($4 OR NOT ao.play_money)
+        ORDER BY ao.edge DESC
+        LIMIT $5
+        "#
+    );
+    let opportunities = sqlx::query_as::<_, ArbitrageWithContext>(&query)
+        .bind(min_edge)
+        .bind(&params.category)
+        .bind(executable_only)
+        .bind(include_play_money)
+        .bind(limit)
+        .fetch_all(&state.db)
+        .await;
+
+    match opportunities {
+        Ok(data) => {
+            let response = ApiResponse::new(data);
+            crate::cache::set(&state.redis, &cache_key, &response, 60).await;
+            Json(response).into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to get arbitrage opportunities: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+                "error": "Failed to fetch arbitrage opportunities"
+            }))).into_response()
+        }
+    }
+}
+
+/// A market's opportunities, open and closed, most recent first.
+async fn get_market_arbitrage(
+    State(state): State<AppState>,
+    Path(market_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let query = format!(
+        r#"
+        {SELECT_WITH_CONTEXT}
+        WHERE ao.market_id = $1
+        ORDER BY ao.detected_at DESC
+        LIMIT 50
+        "#
+    );
+    let opportunities = sqlx::query_as::<_, ArbitrageWithContext>(&query)
+        .bind(market_id)
+        .fetch_all(&state.db)
+        .await;
+
+    match opportunities {
+        Ok(data) => Json(ApiResponse::new(data)).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to get market arbitrage: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+                "error": "Failed to fetch arbitrage opportunities"
+            }))).into_response()
+        }
+    }
+}
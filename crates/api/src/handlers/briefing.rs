@@ -125,6 +125,7 @@ struct LiveMover {
 struct LiveVolume {
     market_id: Uuid,
     title: String,
+    /// In USD.
     total_volume: Option<bigdecimal::BigDecimal>,
     source_count: i64,
 }
@@ -175,11 +176,11 @@ async fn generate_live_briefing(pool: &sqlx::PgPool) -> Result<LiveBriefing, any
     let high_volume = sqlx::query_as::<_, LiveVolume>(
         r#"
         SELECT m.id as market_id, m.title,
-            SUM(sm.volume) as total_volume,
+            SUM(to_usd(sm.volume, sm.currency)) as total_volume,
             COUNT(sm.id) as source_count
         FROM markets m
         JOIN source_markets sm ON sm.market_id = m.id
-        WHERE m.status = 'active' AND sm.volume > 0
+        WHERE m.status = 'active' AND to_usd(sm.volume, sm.currency) > 0
         GROUP BY m.id, m.title
         ORDER BY total_volume DESC
         LIMIT 10
@@ -3,17 +3,26 @@ use axum::{
     extract::{Path, State},
     http::StatusCode,
     response::IntoResponse,
-    routing::get,
+    routing::{get, post},
 };
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 use crate::state::AppState;
+use forecise_consensus::aggregator::AggregationMethod;
+use forecise_consensus::engine::{self, ConsensusConfig, SourceInput, SourceWeight};
+use forecise_consensus::explain::{self, SourceExplanation};
+use forecise_consensus::store::{self, FittedParams};
 use forecise_shared::models::*;
 
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/{market_id}", get(get_consensus))
         .route("/{market_id}/history", get(get_consensus_history))
+        .route("/{market_id}/explain", get(explain_consensus))
+        .route("/{market_id}/what-if", post(what_if_consensus))
 }
 
 async fn get_consensus(
@@ -84,3 +93,198 @@ async fn get_consensus_history(
         }
     }
 }
+
+#[derive(Debug, Serialize)]
+pub struct ConsensusExplanation {
+    pub market_id: Uuid,
+    pub time: DateTime<Utc>,
+    pub consensus_probability: f64,
+    pub extremized_probability: Option<f64>,
+    pub method: String,
+    /// Sources by weight, heaviest first.
+    pub sources: Vec<SourceExplanation>,
+    pub outliers: serde_json::Value,
+    pub stale_sources: serde_json::Value,
+}
+
+/// Break the latest consensus down by source: weight and why, contribution,
+/// and how far the consensus would move without each source.
+async fn explain_consensus(
+    State(state): State<AppState>,
+    Path(market_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let snapshot = sqlx::query_as::<_, ConsensusSnapshot>(
+        r#"
+        SELECT * FROM consensus_snapshots
+        WHERE market_id = $1
+        ORDER BY time DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(market_id)
+    .fetch_optional(&state.db)
+    .await;
+
+    match snapshot {
+        Ok(Some(snapshot)) => {
+            let weights: Vec<SourceWeight> = match serde_json::from_value(snapshot.weights.clone()) {
+                Ok(weights) => weights,
+                Err(e) => {
+                    tracing::error!("Invalid weights on consensus snapshot for {}: {}", market_id, e);
+                    return (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(serde_json::json!({
+                            "error": "Failed to explain consensus"
+                        })),
+                    )
+                        .into_response();
+                }
+            };
+            let method = AggregationMethod::from_stored(&snapshot.aggregation_method, &snapshot.aggregation_params)
+                .unwrap_or_default();
+            let explanation = explain::explain(&weights, method.aggregator().as_ref());
+
+            Json(ApiResponse::new(ConsensusExplanation {
+                market_id,
+                time: snapshot.time,
+                consensus_probability: snapshot.consensus_probability.to_string().parse().unwrap_or(explanation.probability),
+                extremized_probability: snapshot.extremized_probability
+                    .as_ref()
+                    .and_then(|p| p.to_string().parse().ok()),
+                method: explanation.method,
+                sources: explanation.sources,
+                outliers: snapshot.outlier_sources,
+                stale_sources: snapshot.stale_sources,
+            }))
+            .into_response()
+        }
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": "No consensus data found for this market"
+            })),
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::error!("Failed to explain consensus: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": "Failed to explain consensus"
+                })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Changes to a market's inputs for a what-if consensus.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct WhatIfRequest {
+    /// New probabilities for existing sources, by source slug.
+    probabilities: HashMap<String, f64>,
+    /// Source slugs to leave out.
+    exclude: Vec<String>,
+    /// Hypothetical sources to add.
+    add: Vec<SourceInput>,
+    /// Aggregation method to use instead of the market's.
+    aggregation: Option<AggregationMethod>,
+    /// Full consensus config to use instead of the market's.
+    config: Option<ConsensusConfig>,
+}
+
+fn bad_request(error: String) -> axum::response::Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": error })),
+    )
+        .into_response()
+}
+
+/// Recompute the market's consensus with overridden, excluded or added
+/// sources, or another aggregator. Nothing is stored.
+async fn what_if_consensus(
+    State(state): State<AppState>,
+    Path(market_id): Path<Uuid>,
+    Json(req): Json<WhatIfRequest>,
+) -> impl IntoResponse {
+    // Same checks as a stored config
+    if let Some(config) = &req.config
+        && let Err(e) = config.validate()
+    {
+        return bad_request(e.to_string());
+    }
+
+    let loaded = match FittedParams::load(&state.db).await {
+        Ok(fitted) => match req.config.clone() {
+            Some(mut config) => {
+                if config.extremizing_exponent.is_none() {
+                    config.extremizing_exponent = fitted.extremizing_exponent;
+                }
+                store::load_market_inputs(&state.db, market_id, &config, &fitted)
+                    .await
+                    .map(|inputs| (inputs, config))
+            }
+            None => store::load_market(&state.db, market_id, &fitted).await,
+        },
+        Err(e) => Err(e),
+    };
+
+    let (mut inputs, mut config) = match loaded {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            tracing::error!("Failed to load consensus inputs: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": "Failed to compute consensus"
+                })),
+            )
+                .into_response();
+        }
+    };
+    if inputs.is_empty() {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": "No sources found for this market"
+            })),
+        )
+            .into_response();
+    }
+
+    for (slug, probability) in &req.probabilities {
+        if !(0.0..=1.0).contains(probability) {
+            return bad_request(format!("probability for {} must be between 0 and 1", slug));
+        }
+        match inputs.iter_mut().find(|i| &i.source_id == slug) {
+            Some(input) => input.probability = *probability,
+            None => return bad_request(format!("market has no source {}", slug)),
+        }
+    }
+    for slug in &req.exclude {
+        if !inputs.iter().any(|i| &i.source_id == slug) {
+            return bad_request(format!("market has no source {}", slug));
+        }
+    }
+    inputs.retain(|i| !req.exclude.contains(&i.source_id));
+
+    for source in req.add {
+        if !(0.0..=1.0).contains(&source.probability) {
+            return bad_request(format!("probability for {} must be between 0 and 1", source.source_id));
+        }
+        if inputs.iter().any(|i| i.source_id == source.source_id) {
+            return bad_request(format!("source {} is already in the market", source.source_id));
+        }
+        inputs.push(source);
+    }
+    if let Some(aggregation) = req.aggregation {
+        config.aggregation = aggregation;
+    }
+
+    match engine::calculate_consensus_with(&inputs, &config) {
+        Ok(result) => Json(ApiResponse::new(result)).into_response(),
+        Err(e) => bad_request(e.to_string()),
+    }
+}
@@ -0,0 +1,83 @@
+use axum::{
+    Router, Json,
+    extract::{Path, State},
+    routing::{get, put},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use bigdecimal::BigDecimal;
+
+use forecise_shared::models::*;
+use crate::auth::AdminUser;
+use crate::state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_currencies))
+        .route("/{code}", put(update_currency))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CurrencyUpdate {
+    /// USD per unit; `null` excludes the currency from USD figures.
+    pub usd_rate: Option<BigDecimal>,
+}
+
+/// Currencies volumes are reported in and their conversion to USD.
+async fn list_currencies(State(state): State<AppState>) -> impl IntoResponse {
+    let currencies = sqlx::query_as::<_, Currency>("SELECT * FROM currencies ORDER BY code")
+        .fetch_all(&state.db)
+        .await;
+
+    match currencies {
+        Ok(data) => Json(ApiResponse::new(data)).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to list currencies: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+                "error": "Failed to fetch currencies"
+            }))).into_response()
+        }
+    }
+}
+
+/// Set a currency's USD rate, or exclude it. Applies to volume figures
+/// immediately and to the consensus on its next computation.
+async fn update_currency(
+    State(state): State<AppState>,
+    AdminUser(admin): AdminUser,
+    Path(code): Path<String>,
+    Json(update): Json<CurrencyUpdate>,
+) -> impl IntoResponse {
+    if let Some(rate) = &update.usd_rate
+        && rate.sign() == bigdecimal::num_bigint::Sign::Minus
+    {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+            "error": "usd_rate must not be negative"
+        }))).into_response();
+    }
+
+    let currency = sqlx::query_as::<_, Currency>(
+        "UPDATE currencies SET usd_rate = $1, updated_at = NOW() WHERE code = $2 RETURNING *"
+    )
+    .bind(&update.usd_rate)
+    .bind(code.to_uppercase())
+    .fetch_optional(&state.db)
+    .await;
+
+    match currency {
+        Ok(Some(currency)) => {
+            tracing::info!("USD rate of {} set to {:?} by user {}", currency.code, currency.usd_rate, admin.id);
+            Json(ApiResponse::new(currency)).into_response()
+        }
+        Ok(None) => (StatusCode::NOT_FOUND, Json(serde_json::json!({
+            "error": "Currency not found"
+        }))).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to update currency: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+                "error": "Failed to update currency"
+            }))).into_response()
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use axum::{
+    Router, Json,
+    extract::{Path, State},
+    routing::{get, post},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+
+use forecise_shared::models::*;
+use crate::auth::AuthUser;
+use crate::state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", post(create_forecast).get(get_current_forecasts))
+        .route("/market/{market_id}", get(get_forecast_history))
+        .route("/track-record", get(get_track_record))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateForecastRequest {
+    pub market_id: Uuid,
+    pub probability: f64,
+    pub comment: Option<String>,
+}
+
+async fn create_forecast(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Json(req): Json<CreateForecastRequest>,
+) -> impl IntoResponse {
+    if !(0.0..=1.0).contains(&req.probability) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+            "error": "Probability must be between 0 and 1"
+        }))).into_response();
+    }
+
+    let status = sqlx::query_scalar::<_, String>("SELECT status FROM markets WHERE id = $1")
+        .bind(req.market_id)
+        .fetch_optional(&state.db)
+        .await;
+
+    match status {
+        Ok(Some(s)) if s == "active" => {}
+        Ok(Some(_)) => {
+            return (StatusCode::CONFLICT, Json(serde_json::json!({
+                "error": "Market is no longer open for forecasts"
+            }))).into_response();
+        }
+        Ok(None) => {
+            return (StatusCode::NOT_FOUND, Json(serde_json::json!({
+                "error": "Market not found"
+            }))).into_response();
+        }
+        Err(e) => {
+            tracing::error!("Failed to look up market: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+                "error": "Failed to create forecast"
+            }))).into_response();
+        }
+    }
+
+    let probability = {
+        use std::str::FromStr;
+        BigDecimal::from_str(&format!("{:.6}", req.probability)).unwrap_or_default()
+    };
+
+    let result = sqlx::query_as::<_, UserForecast>(
+        r#"
+        INSERT INTO user_forecasts (user_id, market_id, probability, comment)
+        VALUES ($1, $2, $3, $4)
+        RETURNING *
+        "#
+    )
+    .bind(user.id)
+    .bind(req.market_id)
+    .bind(&probability)
+    .bind(&req.comment)
+    .fetch_one(&state.db)
+    .await;
+
+    match result {
+        Ok(forecast) => (StatusCode::CREATED, Json(ApiResponse::new(forecast))).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to create forecast: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+                "error": "Failed to create forecast"
+            }))).into_response()
+        }
+    }
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+struct CurrentForecast {
+    market_id: Uuid,
+    market_title: String,
+    market_status: String,
+    probability: BigDecimal,
+    consensus_probability: Option<BigDecimal>,
+    comment: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+/// The authenticated user's latest forecast on every market they have forecast.
+async fn get_current_forecasts(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> impl IntoResponse {
+    let forecasts = sqlx::query_as::<_, CurrentForecast>(
+        r#"
+        SELECT
+            uf.market_id,
+            m.title as market_title,
+            m.status as market_status,
+            uf.probability,
+            cs.consensus_probability,
+            uf.comment,
+            uf.created_at
+        FROM (
+            SELECT DISTINCT ON (market_id) *
+            FROM user_forecasts
+            WHERE user_id = $1
+            ORDER BY market_id, created_at DESC
+        ) uf
+        JOIN markets m ON uf.market_id = m.id
+        LEFT JOIN LATERAL (
+            SELECT consensus_probability
+            FROM consensus_snapshots
+            WHERE market_id = m.id
+            ORDER BY time DESC
+            LIMIT 1
+        ) cs ON true
+        ORDER BY uf.created_at DESC
+        "#
+    )
+    .bind(user.id)
+    .fetch_all(&state.db)
+    .await;
+
+    match forecasts {
+        Ok(data) => Json(ApiResponse::new(data)).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to get forecasts: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+                "error": "Failed to fetch forecasts"
+            }))).into_response()
+        }
+    }
+}
+
+async fn get_forecast_history(
+    State(state): State<AppState>,
+    user: AuthUser,
+    Path(market_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let history = sqlx::query_as::<_, UserForecast>(
+        r#"
+        SELECT * FROM user_forecasts
+        WHERE user_id = $1 AND market_id = $2
+        ORDER BY created_at DESC
+        "#
+    )
+    .bind(user.id)
+    .bind(market_id)
+    .fetch_all(&state.db)
+    .await;
+
+    match history {
+        Ok(data) => Json(ApiResponse::new(data)).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to get forecast history: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+                "error": "Failed to fetch forecast history"
+            }))).into_response()
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct TrackRecord {
+    total_resolved: i64,
+    brier_score: Option<BigDecimal>,
+    accuracy_pct: Option<BigDecimal>,
+    scores: Vec<ScoredForecast>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+struct ScoredForecast {
+    market_id: Uuid,
+    market_title: String,
+    predicted_probability: BigDecimal,
+    actual_outcome: BigDecimal,
+    brier_score: BigDecimal,
+    resolved_at: DateTime<Utc>,
+}
+
+/// The authenticated user's scored forecasts on resolved markets.
+async fn get_track_record(
+    State(state): State<AppState>,
+    user: AuthUser,
+) -> impl IntoResponse {
+    let scores = sqlx::query_as::<_, ScoredForecast>(
+        r#"
+        SELECT
+            ups.market_id,
+            m.title as market_title,
+            ups.predicted_probability,
+            ups.actual_outcome,
+            ups.brier_score,
+            ups.resolved_at
+        FROM user_prediction_scores ups
+        JOIN markets m ON ups.market_id = m.id
+        WHERE ups.user_id = $1
+        ORDER BY ups.resolved_at DESC
+        "#
+    )
+    .bind(user.id)
+    .fetch_all(&state.db)
+    .await;
+
+    let summary = sqlx::query_as::<_, (i64, Option<BigDecimal>, Option<BigDecimal>)>(
+        r#"
+        SELECT
+            COUNT(*),
+            AVG(brier_score)::DECIMAL(10, 6),
+            ((1 - AVG(brier_score)) * 100)::DECIMAL(10, 4)
+        FROM user_prediction_scores
+        WHERE user_id = $1
+        "#
+    )
+    .bind(user.id)
+    .fetch_one(&state.db)
+    .await;
+
+    match (scores, summary) {
+        (Ok(scores), Ok((total_resolved, brier_score, accuracy_pct))) => {
+            Json(ApiResponse::new(TrackRecord {
+                total_resolved,
+                brier_score,
+                accuracy_pct,
+                scores,
+            })).into_response()
+        }
+        (Err(e), _) | (_, Err(e)) => {
+            tracing::error!("Failed to get track record: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+                "error": "Failed to fetch track record"
+            }))).into_response()
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use axum::{
+    Router, Json,
+    extract::{Query, State},
+    routing::get,
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+
+use forecise_shared::models::*;
+use crate::state::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_lead_lag))
+        .route("/sources", get(get_discovery_shares))
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct LeadLagPair {
+    pub source_a: String,
+    pub source_a_name: String,
+    pub source_b: String,
+    pub source_b_name: String,
+    pub category: Option<String>,
+    pub markets: i32,
+    pub observations: i32,
+    pub window_days: i32,
+    pub step_minutes: i32,
+    /// Positive: `source_a` moves first by this many minutes.
+    pub best_lag_minutes: i32,
+    pub best_correlation: BigDecimal,
+    /// `source_a`'s price discovery share (0.5 = neither leads).
+    pub a_share: BigDecimal,
+    pub leader: Option<String>,
+    pub correlations: serde_json::Value,
+    pub calculated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SourceDiscovery {
+    pub source: String,
+    pub source_name: String,
+    /// Observation-weighted share of price discovery against every other venue.
+    pub discovery_share: f64,
+    pub pairs: i64,
+    pub observations: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LeadLagParams {
+    /// Only pairs including this source slug.
+    pub source: Option<String>,
+    /// Category slug; all categories when absent.
+    pub category: Option<String>,
+    pub min_markets: Option<i32>,
+}
+
+/// Lead-lag per source pair, clearest leader first.
+async fn get_lead_lag(
+    State(state): State<AppState>,
+    Query(params): Query<LeadLagParams>,
+) -> impl IntoResponse {
+    let min_markets = params.min_markets.unwrap_or(1).max(1);
+    let cache_key = format!(
+        "leadlag:pairs:{}:{}:{}",
+        params.source.as_deref().unwrap_or(""),
+        params.category.as_deref().unwrap_or(""),
+        min_markets
+    );
+    if let Some(cached) = crate::cache::get::<serde_json::Value>(&state.redis, &cache_key).await {
+        return Json(cached).into_response();
+    }
+
+    let pairs = sqlx::query_as::<_, LeadLagPair>(
+        r#"
+        SELECT
+            sa.slug as source_a, sa.name as source_a_name,
+            sb.slug as source_b, sb.name as source_b_name,
+            c.slug as category,
+            ll.markets, ll.observations, ll.window_days, ll.step_minutes,
+            ll.best_lag_minutes, ll.best_correlation, ll.a_share,
+            CASE WHEN ll.a_share > 0.5 THEN sa.slug WHEN ll.a_share < 0.5 THEN sb.slug END as leader,
+            ll.correlations, ll.calculated_at
+        FROM lead_lag_stats ll
+        JOIN sources sa ON ll.source_a_id = sa.id
+        JOIN sources sb ON ll.source_b_id = sb.id
+        LEFT JOIN categories c ON ll.category_id = c.id
+        WHERE ($1::text IS NULL AND ll.category_id IS NULL OR c.slug = $1)
+        AND ($2::text IS NULL OR sa.slug = $2 OR sb.slug = $2)
+        AND ll.markets >= $3
+        ORDER BY ABS(ll.a_share - 0.5) DESC
+        "#
+    )
+    .bind(&params.category)
+    .bind(&params.source)
+    .bind(min_markets)
+    .fetch_all(&state.db)
+    .await;
+
+    match pairs {
+        Ok(data) => {
+            let response = ApiResponse::new(data);
+            crate::cache::set(&state.redis, &cache_key, &response, 300).await;
+            Json(response).into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to get lead-lag: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+                "error": "Failed to fetch lead-lag"
+            }))).into_response()
+        }
+    }
+}
+
+/// Each source's price discovery share across its pairs, leaders first.
+async fn get_discovery_shares(
+    State(state): State<AppState>,
+    Query(params): Query<LeadLagParams>,
+) -> impl IntoResponse {
+    let cache_key = format!("leadlag:sources:{}", params.category.as_deref().unwrap_or(""));
+    if let Some(cached) = crate::cache::get::<serde_json::Value>(&state.redis, &cache_key).await {
+        return Json(cached).into_response();
+    }
+
+    let sources = sqlx::query_as::<_, SourceDiscovery>(
+        r#"
+        WITH sides AS (
+            SELECT ll.source_a_id as source_id, ll.a_share as share, ll.observations, ll.category_id
+            FROM lead_lag_stats ll
+            UNION ALL
+            SELECT ll.source_b_id, 1 - ll.a_share, ll.observations, ll.category_id
+            FROM lead_lag_stats ll
+        )
+        SELECT
+            s.slug as source,
+            s.name as source_name,
+            (SUM(sd.share * sd.observations) / NULLIF(SUM(sd.observations), 0))::float8 as discovery_share,
+            COUNT(*) as pairs,
+            SUM(sd.observations)::bigint as observations
+        FROM sides sd
+        JOIN sources s ON sd.source_id = s.id
+        LEFT JOIN categories c ON sd.category_id = c.id
+        WHERE ($1::text IS NULL AND sd.category_id IS NULL OR c.slug = $1)
+        GROUP BY s.slug, s.name
+        HAVING SUM(sd.observations) > 0
+        ORDER BY discovery_share DESC
+        "#
+    )
+    .bind(&params.category)
+    .fetch_all(&state.db)
+    .await;
+
+    match sources {
+        Ok(data) => {
+            let response = ApiResponse::new(data);
+            crate::cache::set(&state.redis, &cache_key, &response, 300).await;
+            Json(response).into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to get discovery shares: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({
+                "error": "Failed to fetch discovery shares"
+            }))).into_response()
+        }
+    }
+}
@@ -3,12 +3,14 @@ use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
-    routing::get,
+    routing::{get, put},
 };
 use serde::Deserialize;
 use uuid::Uuid;
 
+use crate::auth::AdminUser;
 use crate::state::AppState;
+use forecise_consensus::engine::ConsensusConfig;
 use forecise_shared::models::*;
 
 pub fn routes() -> Router<AppState> {
@@ -17,6 +19,7 @@ pub fn routes() -> Router<AppState> {
         .route("/{id}", get(get_market))
         .route("/{id}/odds", get(get_market_odds))
         .route("/{id}/sources", get(get_market_sources))
+        .route("/{id}/consensus-config", put(update_consensus_config))
 }
 
 #[derive(Debug, Deserialize)]
@@ -58,6 +61,7 @@ async fn list_markets(
             m.status,
             cs.consensus_probability,
             COUNT(DISTINCT sm.id) as source_count,
+            SUM(to_usd(sm.volume, sm.currency)) as volume_usd,
             m.updated_at
         FROM markets m
         LEFT JOIN categories c ON m.category_id = c.id
@@ -132,11 +136,15 @@ async fn get_market(
                     s.slug as source_slug,
                     sm.current_probability as probability,
                     sm.volume,
+                    sm.currency,
+                    to_usd(sm.volume, sm.currency) as volume_usd,
                     ar.accuracy_pct,
                     sm.external_url
                 FROM source_markets sm
                 JOIN sources s ON sm.source_id = s.id
-                LEFT JOIN accuracy_records ar ON ar.source_id = s.id AND ar.category_id = $2
+                LEFT JOIN accuracy_records ar
+                    ON ar.source_id = s.id AND ar.category_id IS NOT DISTINCT FROM $2
+                    AND ar.horizon = 'final' AND ar.window_days IS NULL
                 WHERE sm.market_id = $1
                 ORDER BY ar.accuracy_pct DESC NULLS LAST
                 "#,
@@ -172,7 +180,10 @@ async fn get_market(
             .unwrap_or(None)
             .map(|cs| ConsensusInfo {
                 probability: cs.consensus_probability,
+                extremized_probability: cs.extremized_probability,
                 confidence: cs.confidence_score,
+                interval_lower: cs.interval_lower,
+                interval_upper: cs.interval_upper,
                 source_count: cs.source_count,
                 agreement: cs.agreement_score,
             });
@@ -227,7 +238,7 @@ async fn get_market_odds(
 
     let odds = sqlx::query_as::<_, OddsHistory>(
         r#"
-        SELECT oh.time, oh.source_market_id, oh.probability, oh.volume, oh.trade_count
+        SELECT oh.time, oh.source_market_id, oh.probability, oh.volume, oh.currency, oh.trade_count
         FROM odds_history oh
         JOIN source_markets sm ON oh.source_market_id = sm.id
         WHERE sm.market_id = $1
@@ -282,3 +293,70 @@ async fn get_market_sources(
         }
     }
 }
+
+/// Set the market's consensus configuration (aggregation method and
+/// thresholds). Only the fields sent are stored, so the rest keep following
+/// the engine defaults. Takes effect on the next consensus computation.
+async fn update_consensus_config(
+    State(state): State<AppState>,
+    AdminUser(admin): AdminUser,
+    Path(id): Path<Uuid>,
+    Json(overrides): Json<serde_json::Value>,
+) -> impl IntoResponse {
+    let bad_request = |message: String| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": message
+            })),
+        )
+            .into_response()
+    };
+
+    let Some(fields) = overrides.as_object() else {
+        return bad_request("consensus config must be a JSON object".to_string());
+    };
+    let known = serde_json::to_value(ConsensusConfig::default()).unwrap_or_default();
+    if let Some(unknown) = fields.keys().find(|k| known.get(k.as_str()).is_none()) {
+        return bad_request(format!("unknown consensus config field `{}`", unknown));
+    }
+    let config: ConsensusConfig = match serde_json::from_value(overrides.clone()) {
+        Ok(config) => config,
+        Err(e) => return bad_request(format!("invalid consensus config: {}", e)),
+    };
+    if let Err(e) = config.validate() {
+        return bad_request(e.to_string());
+    }
+
+    let result = sqlx::query(
+        "UPDATE markets SET consensus_config = $1, updated_at = NOW() WHERE id = $2",
+    )
+    .bind(&overrides)
+    .bind(id)
+    .execute(&state.db)
+    .await;
+
+    match result {
+        Ok(r) if r.rows_affected() > 0 => {
+            tracing::info!("Consensus config of market {} set to {} by user {}", id, overrides, admin.id);
+            Json(ApiResponse::new(config)).into_response()
+        }
+        Ok(_) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": "Market not found"
+            })),
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::error!("Failed to update consensus config: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": "Failed to update consensus config"
+                })),
+            )
+                .into_response()
+        }
+    }
+}
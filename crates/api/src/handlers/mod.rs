@@ -4,10 +4,14 @@ use crate::state::AppState;
 
 pub mod accuracy;
 pub mod alerts;
+pub mod arbitrage;
 pub mod ask;
 pub mod briefing;
 pub mod consensus;
+pub mod currencies;
+pub mod forecasts;
 pub mod health;
+pub mod leadlag;
 pub mod markets;
 pub mod movements;
 pub mod whales;
@@ -19,8 +23,12 @@ pub fn api_routes() -> Router<AppState> {
         .nest("/accuracy", accuracy::routes())
         .nest("/ask", ask::routes())
         .nest("/consensus", consensus::routes())
+        .nest("/currencies", currencies::routes())
+        .nest("/forecasts", forecasts::routes())
         .nest("/movements", movements::routes())
         .nest("/alerts", alerts::routes())
+        .nest("/arbitrage", arbitrage::routes())
+        .nest("/lead-lag", leadlag::routes())
         .nest("/whales", whales::routes())
         .nest("/briefing", briefing::routes())
 }
@@ -0,0 +1,253 @@
+//! Pooling methods for combining weighted source probabilities.
+//!
+//! Every aggregator receives the source probabilities together with weights
+//! that already sum to 1 and returns a single pooled probability.
+
+use serde::{Deserialize, Serialize};
+
+/// Probabilities are clamped to `[EPSILON, 1 - EPSILON]` before moving to
+/// log-odds space so that a source at 0% or 100% does not dominate the pool.
+pub const LOG_ODDS_EPSILON: f64 = 1e-4;
+
+/// Pools weighted source probabilities into a single probability.
+pub trait Aggregator: Send + Sync {
+    /// Stable name, recorded on consensus snapshots.
+    fn name(&self) -> &'static str;
+
+    /// Pool `probabilities` under `weights`. Both slices have the same length
+    /// and the weights sum to 1.
+    fn aggregate(&self, probabilities: &[f64], weights: &[f64]) -> f64;
+}
+
+/// Weighted arithmetic mean of probabilities.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LinearPool;
+
+impl Aggregator for LinearPool {
+    fn name(&self) -> &'static str {
+        "linear_pool"
+    }
+
+    fn aggregate(&self, probabilities: &[f64], weights: &[f64]) -> f64 {
+        probabilities.iter().zip(weights).map(|(p, w)| p * w).sum()
+    }
+}
+
+/// Weighted median: the probability at which half of the weight lies on either side.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WeightedMedian;
+
+impl Aggregator for WeightedMedian {
+    fn name(&self) -> &'static str {
+        "weighted_median"
+    }
+
+    fn aggregate(&self, probabilities: &[f64], weights: &[f64]) -> f64 {
+        let sorted = sorted_pairs(probabilities, weights);
+        let total: f64 = sorted.iter().map(|(_, w)| w).sum();
+        let half = total / 2.0;
+
+        let mut cumulative = 0.0;
+        for (i, (p, w)) in sorted.iter().enumerate() {
+            cumulative += w;
+            if (cumulative - half).abs() < 1e-12 {
+                // Exactly half the weight on each side: average the two middle values.
+                let next = sorted.get(i + 1).map(|(p, _)| *p).unwrap_or(*p);
+                return (p + next) / 2.0;
+            }
+            if cumulative > half {
+                return *p;
+            }
+        }
+        sorted.last().map(|(p, _)| *p).unwrap_or(0.5)
+    }
+}
+
+/// Weighted geometric mean of odds, i.e. a weighted mean in log-odds space.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogOddsPool;
+
+impl Aggregator for LogOddsPool {
+    fn name(&self) -> &'static str {
+        "log_odds_pool"
+    }
+
+    fn aggregate(&self, probabilities: &[f64], weights: &[f64]) -> f64 {
+        let pooled: f64 = probabilities.iter()
+            .zip(weights)
+            .map(|(p, w)| w * logit(*p))
+            .sum();
+        sigmoid(pooled)
+    }
+}
+
+/// Weighted mean after discarding `trim_fraction` of the weight from each tail.
+#[derive(Debug, Clone, Copy)]
+pub struct TrimmedMean {
+    pub trim_fraction: f64,
+}
+
+impl Aggregator for TrimmedMean {
+    fn name(&self) -> &'static str {
+        "trimmed_mean"
+    }
+
+    fn aggregate(&self, probabilities: &[f64], weights: &[f64]) -> f64 {
+        let sorted = sorted_pairs(probabilities, weights);
+        let total: f64 = sorted.iter().map(|(_, w)| w).sum();
+        let trim = self.trim_fraction.clamp(0.0, 0.49) * total;
+        let (lo, hi) = (trim, total - trim);
+
+        // Keep the part of each source's weight interval that falls inside [lo, hi].
+        let mut cumulative = 0.0;
+        let mut kept_sum = 0.0;
+        let mut weighted_sum = 0.0;
+        for (p, w) in &sorted {
+            let start = cumulative;
+            cumulative += w;
+            let kept = (cumulative.min(hi) - start.max(lo)).max(0.0);
+            kept_sum += kept;
+            weighted_sum += kept * p;
+        }
+
+        if kept_sum > 0.0 {
+            weighted_sum / kept_sum
+        } else {
+            WeightedMedian.aggregate(probabilities, weights)
+        }
+    }
+}
+
+/// A named, serializable aggregation method with its parameters.
+///
+/// Stored as the `aggregation` field of a market's consensus config, e.g.
+/// `{"name": "trimmed_mean", "trim_fraction": 0.2}`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "name", rename_all = "snake_case")]
+pub enum AggregationMethod {
+    #[default]
+    LinearPool,
+    WeightedMedian,
+    LogOddsPool,
+    TrimmedMean {
+        #[serde(default = "default_trim_fraction")]
+        trim_fraction: f64,
+    },
+}
+
+fn default_trim_fraction() -> f64 {
+    0.1
+}
+
+impl AggregationMethod {
+    /// Build the aggregator this method describes.
+    pub fn aggregator(&self) -> Box<dyn Aggregator> {
+        match self {
+            AggregationMethod::LinearPool => Box::new(LinearPool),
+            AggregationMethod::WeightedMedian => Box::new(WeightedMedian),
+            AggregationMethod::LogOddsPool => Box::new(LogOddsPool),
+            AggregationMethod::TrimmedMean { trim_fraction } => Box::new(TrimmedMean {
+                trim_fraction: *trim_fraction,
+            }),
+        }
+    }
+
+    /// Rebuild a method from a stored name and parameters. `None` for an
+    /// unknown name.
+    pub fn from_stored(name: &str, params: &serde_json::Value) -> Option<Self> {
+        let mut value = match params {
+            serde_json::Value::Object(map) => map.clone(),
+            _ => serde_json::Map::new(),
+        };
+        value.insert("name".into(), name.into());
+        serde_json::from_value(serde_json::Value::Object(value)).ok()
+    }
+
+    /// The method's parameters without its name, for storage next to the name.
+    pub fn params(&self) -> serde_json::Value {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        if let Some(map) = value.as_object_mut() {
+            map.remove("name");
+        }
+        value
+    }
+}
+
+/// Log-odds of a probability, clamped away from 0 and 1.
+pub fn logit(p: f64) -> f64 {
+    let p = p.clamp(LOG_ODDS_EPSILON, 1.0 - LOG_ODDS_EPSILON);
+    (p / (1.0 - p)).ln()
+}
+
+/// Inverse of [`logit`].
+pub fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+fn sorted_pairs(probabilities: &[f64], weights: &[f64]) -> Vec<(f64, f64)> {
+    let mut pairs: Vec<(f64, f64)> = probabilities.iter().copied().zip(weights.iter().copied()).collect();
+    pairs.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROBS: [f64; 4] = [0.2, 0.6, 0.65, 0.7];
+    const EQUAL: [f64; 4] = [0.25, 0.25, 0.25, 0.25];
+
+    #[test]
+    fn test_linear_pool() {
+        let p = LinearPool.aggregate(&PROBS, &EQUAL);
+        assert!((p - 0.5375).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_weighted_median() {
+        // Equal weights on an even count: average of the middle two
+        let p = WeightedMedian.aggregate(&PROBS, &EQUAL);
+        assert!((p - 0.625).abs() < 1e-10);
+
+        // Heavy weight on one source pulls the median to it
+        let p = WeightedMedian.aggregate(&PROBS, &[0.6, 0.1, 0.1, 0.2]);
+        assert!((p - 0.2).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_log_odds_pool_is_geometric_mean_of_odds() {
+        let p = LogOddsPool.aggregate(&[0.2, 0.8], &[0.5, 0.5]);
+        assert!((p - 0.5).abs() < 1e-10);
+
+        // Odds 9:1 and 1:1 -> geometric mean 3:1 -> 0.75
+        let p = LogOddsPool.aggregate(&[0.9, 0.5], &[0.5, 0.5]);
+        assert!((p - 0.75).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_trimmed_mean_drops_tails() {
+        // Trimming 25% from each end of four equal weights leaves the middle two
+        let p = TrimmedMean { trim_fraction: 0.25 }.aggregate(&PROBS, &EQUAL);
+        assert!((p - 0.625).abs() < 1e-10);
+
+        // No trimming is the linear pool
+        let p = TrimmedMean { trim_fraction: 0.0 }.aggregate(&PROBS, &EQUAL);
+        assert!((p - LinearPool.aggregate(&PROBS, &EQUAL)).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_method_from_config() {
+        let method: AggregationMethod =
+            serde_json::from_str(r#"{"name": "trimmed_mean", "trim_fraction": 0.2}"#).unwrap();
+        assert_eq!(method, AggregationMethod::TrimmedMean { trim_fraction: 0.2 });
+        assert_eq!(method.aggregator().name(), "trimmed_mean");
+        assert_eq!(method.params(), serde_json::json!({ "trim_fraction": 0.2 }));
+
+        let method: AggregationMethod = serde_json::from_str(r#"{"name": "trimmed_mean"}"#).unwrap();
+        assert_eq!(method, AggregationMethod::TrimmedMean { trim_fraction: 0.1 });
+        // Round-trips through the stored name and params
+        let stored = AggregationMethod::TrimmedMean { trim_fraction: 0.2 };
+        assert_eq!(AggregationMethod::from_stored("trimmed_mean", &stored.params()), Some(stored));
+        assert_eq!(AggregationMethod::from_stored("unknown", &serde_json::json!({})), None);
+    }
+}
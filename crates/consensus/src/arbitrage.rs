@@ -0,0 +1,231 @@
+//! Cross-venue arbitrage on binary markets.
+//!
+//! Buying YES on one venue and NO on another pays exactly one unit whatever
+//! the outcome. The edge is that unit minus what the two legs cost, trading
+//! fees included, minus the worst case of the fees charged on the winning
+//! leg. Prices come from the best bid and ask where the venue quotes them,
+//! otherwise from the mid (plus or minus half the spread, if known), in which
+//! case the edge is indicative rather than executable. Play-money venues are
+//! never paired with real-money ones: there is no exchange rate to arbitrage.
+
+use serde::{Deserialize, Serialize};
+
+/// How a venue charges for a contract paying one unit. Stored per source,
+/// e.g. `{"name": "kalshi_taker", "rate": 0.07}`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "name", rename_all = "snake_case")]
+pub enum FeeModel {
+    #[default]
+    None,
+    /// `rate` times the price paid, on entry.
+    Proportional { rate: f64 },
+    /// `rate * p * (1 - p)` per contract bought at `p`, on entry (Kalshi).
+    KalshiTaker { rate: f64 },
+    /// `rate` of the profit on a winning contract, then `withdrawal_rate` of
+    /// the proceeds (PredictIt).
+    ProfitShare {
+        rate: f64,
+        #[serde(default)]
+        withdrawal_rate: f64,
+    },
+}
+
+impl FeeModel {
+    /// Fee paid when buying a contract at `price`.
+    pub fn entry_fee(&self, price: f64) -> f64 {
+        match self {
+            FeeModel::None | FeeModel::ProfitShare { .. } => 0.0,
+            FeeModel::Proportional { rate } => rate * price,
+            FeeModel::KalshiTaker { rate } => rate * price * (1.0 - price),
+        }
+    }
+
+    /// Fee paid when a contract bought at `price` pays out.
+    pub fn settlement_fee(&self, price: f64) -> f64 {
+        match self {
+            FeeModel::ProfitShare { rate, withdrawal_rate } => {
+                let profit_fee = rate * (1.0 - price);
+                profit_fee + withdrawal_rate * (1.0 - profit_fee)
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+/// Where a price came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteBasis {
+    /// The venue's best bid or ask.
+    BidAsk,
+    /// Mid plus or minus half the reported spread.
+    Spread,
+    /// Mid (last price or AMM price) only.
+    Mid,
+}
+
+/// A venue's quote for the YES side of a linked market.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VenueQuote {
+    pub source_id: String,
+    pub source_name: String,
+    /// Last or mid probability.
+    pub probability: f64,
+    pub best_bid: Option<f64>,
+    pub best_ask: Option<f64>,
+    pub spread: Option<f64>,
+    pub fees: FeeModel,
+    pub play_money: bool,
+}
+
+impl VenueQuote {
+    /// Cost of one YES contract.
+    pub fn yes_price(&self) -> (f64, QuoteBasis) {
+        match (self.best_ask, self.spread) {
+            (Some(ask), _) => (ask, QuoteBasis::BidAsk),
+            (None, Some(spread)) => ((self.probability + spread / 2.0).min(1.0), QuoteBasis::Spread),
+            (None, None) => (self.probability, QuoteBasis::Mid),
+        }
+    }
+
+    /// Cost of one NO contract, i.e. one minus the YES bid.
+    pub fn no_price(&self) -> (f64, QuoteBasis) {
+        match (self.best_bid, self.spread) {
+            (Some(bid), _) => (1.0 - bid, QuoteBasis::BidAsk),
+            (None, Some(spread)) => ((1.0 - self.probability + spread / 2.0).min(1.0), QuoteBasis::Spread),
+            (None, None) => (1.0 - self.probability, QuoteBasis::Mid),
+        }
+    }
+}
+
+/// YES on one venue plus NO on another.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Opportunity {
+    /// Index of the venue to buy YES on, in the quotes passed in.
+    pub yes_venue: usize,
+    /// Index of the venue to buy NO on.
+    pub no_venue: usize,
+    pub yes_price: f64,
+    pub no_price: f64,
+    /// Entry fees plus the worse of the two settlement fees.
+    pub fees: f64,
+    /// Locked-in profit per unit of payout, after fees.
+    pub edge: f64,
+    /// `edge` over the capital the two legs tie up.
+    pub return_pct: f64,
+    /// Both legs priced from a live bid or ask.
+    pub executable: bool,
+    /// Both legs on play-money venues.
+    pub play_money: bool,
+}
+
+/// Price YES on `yes` against NO on `no`. `None` when one venue is play money
+/// and the other is not.
+pub fn price_pair(yes: &VenueQuote, no: &VenueQuote) -> Option<(f64, f64, f64, bool)> {
+    if yes.play_money != no.play_money {
+        return None;
+    }
+    let (yes_price, yes_basis) = yes.yes_price();
+    let (no_price, no_basis) = no.no_price();
+    let fees = yes.fees.entry_fee(yes_price)
+        + no.fees.entry_fee(no_price)
+        + yes.fees.settlement_fee(yes_price).max(no.fees.settlement_fee(no_price));
+    let executable = yes_basis == QuoteBasis::BidAsk && no_basis == QuoteBasis::BidAsk;
+    Some((yes_price, no_price, fees, executable))
+}
+
+/// The better direction for every pair of venues that can be traded against
+/// each other, best edge first. Negative edges are included; filter as needed.
+pub fn find_opportunities(quotes: &[VenueQuote]) -> Vec<Opportunity> {
+    let mut opportunities = Vec::new();
+    for i in 0..quotes.len() {
+        for j in (i + 1)..quotes.len() {
+            let best = [(i, j), (j, i)]
+                .into_iter()
+                .filter_map(|(y, n)| {
+                    let (yes_price, no_price, fees, executable) = price_pair(&quotes[y], &quotes[n])?;
+                    let cost = yes_price + no_price + fees;
+                    let edge = 1.0 - cost;
+                    Some(Opportunity {
+                        yes_venue: y,
+                        no_venue: n,
+                        yes_price,
+                        no_price,
+                        fees,
+                        edge,
+                        return_pct: if cost > 0.0 { edge / cost * 100.0 } else { 0.0 },
+                        executable,
+                        play_money: quotes[y].play_money,
+                    })
+                })
+                .max_by(|a, b| a.edge.partial_cmp(&b.edge).unwrap_or(std::cmp::Ordering::Equal));
+            opportunities.extend(best);
+        }
+    }
+    opportunities.sort_by(|a, b| b.edge.partial_cmp(&a.edge).unwrap_or(std::cmp::Ordering::Equal));
+    opportunities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(id: &str, bid: f64, ask: f64, fees: FeeModel) -> VenueQuote {
+        VenueQuote {
+            source_id: id.into(),
+            source_name: id.into(),
+            probability: (bid + ask) / 2.0,
+            best_bid: Some(bid),
+            best_ask: Some(ask),
+            spread: Some(ask - bid),
+            fees,
+            play_money: false,
+        }
+    }
+
+    #[test]
+    fn test_fee_free_arbitrage() {
+        // YES at 0.40 on a, NO at 1 - 0.55 = 0.45 on b: 0.15 locked in
+        let quotes = [
+            quote("a", 0.39, 0.40, FeeModel::None),
+            quote("b", 0.55, 0.56, FeeModel::None),
+        ];
+        let found = find_opportunities(&quotes);
+        assert_eq!(found.len(), 1);
+        let best = &found[0];
+        assert_eq!((best.yes_venue, best.no_venue), (0, 1));
+        assert!((best.edge - 0.15).abs() < 1e-10);
+        assert!((best.return_pct - 0.15 / 0.85 * 100.0).abs() < 1e-8);
+        assert!(best.executable);
+    }
+
+    #[test]
+    fn test_fees_eat_the_edge() {
+        let yes = quote("kalshi", 0.47, 0.48, FeeModel::KalshiTaker { rate: 0.07 });
+        let no = quote("predictit", 0.50, 0.51, FeeModel::ProfitShare { rate: 0.10, withdrawal_rate: 0.05 });
+        let (yes_price, no_price, fees, _) = price_pair(&yes, &no).unwrap();
+        assert!((yes_price + no_price - 0.98).abs() < 1e-10);
+        // 0.07 * 0.48 * 0.52 on entry, then PredictIt's 10% of 0.50 profit
+        // and 5% of what is left when NO wins
+        let expected = 0.07 * 0.48 * 0.52 + 0.05 + 0.05 * 0.95;
+        assert!((fees - expected).abs() < 1e-10);
+        assert!(find_opportunities(&[yes, no])[0].edge < 0.0);
+    }
+
+    #[test]
+    fn test_play_money_is_not_paired_with_real_money() {
+        let real = quote("polymarket", 0.30, 0.31, FeeModel::None);
+        let mut play = quote("manifold", 0.60, 0.61, FeeModel::None);
+        play.play_money = true;
+        assert!(find_opportunities(&[real, play.clone()]).is_empty());
+
+        let mut other = quote("other", 0.30, 0.31, FeeModel::None);
+        other.play_money = true;
+        other.best_bid = None;
+        other.best_ask = None;
+        other.spread = None;
+        let found = find_opportunities(&[play, other]);
+        assert!(found[0].play_money);
+        assert!(!found[0].executable);
+    }
+}
@@ -0,0 +1,527 @@
+//! Consensus backtesting.
+//!
+//! Replays source odds on resolved markets and rebuilds the consensus as it
+//! would have been at fixed horizons before resolution (e.g. 1, 7 and 30 days
+//! out). Each configuration is scored against the real outcomes next to every
+//! individual source, so changes to weighting or aggregation can be compared
+//! on the same markets.
+//!
+//! Source accuracy is rebuilt point-in-time from scores resolved before each
+//! cutoff, so a market's own outcome never leaks into its weights. Learned
+//! weights, for configurations that use them, are likewise refitted every
+//! [`WEIGHT_REFIT_DAYS`] days on the markets resolved before each refit.
+//! Depth is weighted by the traded volume recorded at the cutoff only: odds
+//! history does not keep liquidity, spread or forecaster counts, and their
+//! current values would leak the market's later state.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::calibration::{self, CalibrationBin};
+use crate::engine::{self, AccuracyStats, ConsensusConfig, SourceInput};
+use crate::learned::{self, FitObjective, WeightSample};
+
+/// How often learned weights are refitted during a replay.
+pub const WEIGHT_REFIT_DAYS: i64 = 30;
+
+/// A resolved market with the odds history of each of its sources.
+#[derive(Debug, Clone)]
+pub struct ReplayMarket {
+    pub market_id: String,
+    pub title: String,
+    pub category: Option<String>,
+    pub resolved_at: DateTime<Utc>,
+    /// Resolution value in `[0, 1]`.
+    pub outcome: f64,
+    pub sources: Vec<ReplaySource>,
+}
+
+/// One source's market, with its odds history sorted by time.
+#[derive(Debug, Clone)]
+pub struct ReplaySource {
+    pub source_id: String,
+    pub source_name: String,
+    pub history: Vec<OddsPoint>,
+    pub play_money: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct OddsPoint {
+    pub time: DateTime<Utc>,
+    pub probability: f64,
+    pub volume: Option<f64>,
+}
+
+/// A scored source prediction, used to rebuild accuracy as of a cutoff.
+#[derive(Debug, Clone)]
+pub struct PastScore {
+    pub source_id: String,
+    pub category: Option<String>,
+    pub brier_score: f64,
+    pub resolved_at: DateTime<Utc>,
+}
+
+/// How long before resolution the consensus is rebuilt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Horizon(pub Duration);
+
+impl Horizon {
+    /// Parse `"12h"`, `"1d"`, `"7d"` or `"2w"`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let (n, unit) = s.split_at(s.len().checked_sub(1)?);
+        let n: i64 = n.parse().ok()?;
+        let duration = match unit {
+            "h" => Duration::hours(n),
+            "d" => Duration::days(n),
+            "w" => Duration::weeks(n),
+            _ => return None,
+        };
+        Some(Self(duration))
+    }
+
+    pub fn label(&self) -> String {
+        let hours = self.0.num_hours();
+        if hours % 24 == 0 {
+            format!("{}d", hours / 24)
+        } else {
+            format!("{}h", hours)
+        }
+    }
+}
+
+/// Scores for one forecaster (a configuration or a source) at one horizon.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreSummary {
+    pub name: String,
+    pub markets: usize,
+    /// Mean Brier score (lower is better).
+    pub brier: f64,
+    /// Mean log probability of the outcome (higher is better, at most 0).
+    pub log_score: f64,
+    pub calibration: Vec<CalibrationBin>,
+    /// Brier of the first configuration on exactly the markets this source
+    /// covered. `None` for configurations.
+    pub consensus_brier_same_markets: Option<f64>,
+}
+
+/// All results at one horizon.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HorizonReport {
+    pub horizon: String,
+    /// Markets with at least one source quoted before the cutoff.
+    pub markets: usize,
+    pub configs: Vec<ScoreSummary>,
+    pub sources: Vec<ScoreSummary>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestReport {
+    pub horizons: Vec<HorizonReport>,
+    /// Where the replay departs from the live consensus.
+    pub notes: Vec<String>,
+}
+
+/// Reported when a configuration weights by depth.
+pub const DEPTH_NOTE: &str = "Depth weighting uses traded volume as of each cutoff; \
+    liquidity, spread and forecaster counts are not kept in odds history and are left out.";
+
+/// Replay every market at every horizon under each named configuration.
+///
+/// The first configuration is the reference that sources are compared against.
+pub fn run_backtest(
+    markets: &[ReplayMarket],
+    scores: &[PastScore],
+    configs: &[(String, ConsensusConfig)],
+    horizons: &[Horizon],
+) -> BacktestReport {
+    let weights = configs.iter()
+        .any(|(_, c)| c.learned_weights)
+        .then(|| WeightHistory::fit(markets));
+    let horizons = horizons.iter()
+        .map(|h| run_horizon(markets, scores, weights.as_ref(), configs, *h))
+        .collect();
+    let notes = configs.iter()
+        .any(|(_, c)| c.liquidity_weighting)
+        .then(|| DEPTH_NOTE.to_string())
+        .into_iter()
+        .collect();
+    BacktestReport { horizons, notes }
+}
+
+/// Learned weights by source id, per category (`None` = all categories).
+type WeightSets = HashMap<Option<String>, BTreeMap<String, f64>>;
+
+/// Learned weight sets as they would have been fitted over time, each on the
+/// markets resolved before its fit. Only sets that beat equal weights out of sample are kept, as in the
+/// scoring worker.
+struct WeightHistory {
+    fits: Vec<(DateTime<Utc>, WeightSets)>,
+}
+
+impl WeightHistory {
+    fn fit(markets: &[ReplayMarket]) -> Self {
+        let horizon = Duration::hours(learned::WEIGHT_FIT_HORIZON_HOURS);
+        let mut samples: Vec<(DateTime<Utc>, Option<&str>, WeightSample)> = markets.iter()
+            .map(|m| {
+                let forecasts = m.sources.iter()
+                    .filter_map(|s| {
+                        let point = s.history.iter().rev().find(|p| p.time <= m.resolved_at - horizon)?;
+                        Some((s.source_id.clone(), point.probability))
+                    })
+                    .collect();
+                (m.resolved_at, m.category.as_deref(), WeightSample { forecasts, outcome: m.outcome })
+            })
+            .filter(|(_, _, sample)| sample.forecasts.len() >= 2)
+            .collect();
+        samples.sort_by_key(|(resolved_at, _, _)| *resolved_at);
+
+        let mut fits = Vec::new();
+        let (Some(first), Some(last)) = (samples.first(), samples.last()) else {
+            return Self { fits };
+        };
+        let step = Duration::days(WEIGHT_REFIT_DAYS);
+        let mut at = first.0 + step;
+        while at <= last.0 {
+            let resolved = samples.partition_point(|(resolved_at, _, _)| *resolved_at < at);
+            let mut by_category: BTreeMap<Option<&str>, Vec<WeightSample>> = BTreeMap::new();
+            for (_, category, sample) in &samples[..resolved] {
+                if category.is_some() {
+                    by_category.entry(*category).or_default().push(sample.clone());
+                }
+                by_category.entry(None).or_default().push(sample.clone());
+            }
+            let sets = by_category.into_iter()
+                .filter_map(|(category, samples)| {
+                    learned::fit_source_weights(&samples, FitObjective::LogLoss)
+                        .filter(|fit| fit.beats_baseline())
+                        .map(|fit| (category.map(str::to_string), fit.weights))
+                })
+                .collect();
+            fits.push((at, sets));
+            at += step;
+        }
+        Self { fits }
+    }
+
+    /// The weights in force at `cutoff` for a market in `category`: the
+    /// category's set from the latest fit before it, then the all-categories set.
+    fn as_of(&self, cutoff: DateTime<Utc>, category: Option<&str>) -> Option<&BTreeMap<String, f64>> {
+        let index = self.fits.partition_point(|(at, _)| *at <= cutoff).checked_sub(1)?;
+        let sets = &self.fits[index].1;
+        category
+            .and_then(|c| sets.get(&Some(c.to_string())))
+            .or_else(|| sets.get(&None))
+    }
+}
+
+/// A source's forecasts at one horizon, next to the reference consensus on
+/// the same markets.
+struct SourceTally {
+    name: String,
+    own: Vec<(f64, f64)>,
+    reference: Vec<(f64, f64)>,
+}
+
+fn run_horizon(
+    markets: &[ReplayMarket],
+    scores: &[PastScore],
+    weights: Option<&WeightHistory>,
+    configs: &[(String, ConsensusConfig)],
+    horizon: Horizon,
+) -> HorizonReport {
+    let mut config_forecasts: Vec<Vec<(f64, f64)>> = vec![Vec::new(); configs.len()];
+    let mut source_forecasts: BTreeMap<String, SourceTally> = BTreeMap::new();
+    let mut market_count = 0;
+
+    for market in markets {
+        let cutoff = market.resolved_at - horizon.0;
+        let learned = weights.and_then(|w| w.as_of(cutoff, market.category.as_deref()));
+        let inputs = inputs_at(market, scores, learned, cutoff, None);
+        if inputs.is_empty() {
+            continue;
+        }
+
+        let mut reference = None;
+        for (i, (_, config)) in configs.iter().enumerate() {
+            let config = ConsensusConfig { as_of: Some(cutoff), ..config.clone() };
+            let windowed;
+            let inputs = match config.accuracy_window_days {
+                Some(days) => {
+                    windowed = inputs_at(market, scores, learned, cutoff, Some(days));
+                    &windowed
+                }
+                None => &inputs,
+            };
+            if let Ok(result) = engine::calculate_consensus_with(inputs, &config) {
+                config_forecasts[i].push((result.probability, market.outcome));
+                if i == 0 {
+                    reference = Some(result.probability);
+                }
+            }
+        }
+        let Some(reference) = reference else { continue };
+        market_count += 1;
+
+        for input in &inputs {
+            let tally = source_forecasts.entry(input.source_id.clone())
+                .or_insert_with(|| SourceTally {
+                    name: input.source_name.clone(),
+                    own: Vec::new(),
+                    reference: Vec::new(),
+                });
+            tally.own.push((input.probability, market.outcome));
+            tally.reference.push((reference, market.outcome));
+        }
+    }
+
+    let configs = configs.iter()
+        .zip(config_forecasts)
+        .map(|((name, _), forecasts)| summarize(name, &forecasts, None))
+        .collect();
+    let sources = source_forecasts.into_values()
+        .map(|t| summarize(&t.name, &t.own, Some(mean_brier(&t.reference))))
+        .collect();
+
+    HorizonReport {
+        horizon: horizon.label(),
+        markets: market_count,
+        configs,
+        sources,
+    }
+}
+
+/// Build the consensus inputs for a market as they stood at `cutoff`, with
+/// accuracy over the preceding `window_days` or all time and the learned
+/// weights in force then.
+fn inputs_at(
+    market: &ReplayMarket,
+    scores: &[PastScore],
+    learned: Option<&BTreeMap<String, f64>>,
+    cutoff: DateTime<Utc>,
+    window_days: Option<i32>,
+) -> Vec<SourceInput> {
+    let since = window_days.map(|d| cutoff - Duration::days(d as i64));
+    market.sources.iter()
+        .filter_map(|source| {
+            let index = source.history.iter().rposition(|p| p.time <= cutoff)?;
+            let point = &source.history[index];
+            let category = market.category.as_deref()
+                .and_then(|c| accuracy_as_of(scores, &source.source_id, Some(c), since, cutoff));
+            let overall = accuracy_as_of(scores, &source.source_id, None, since, cutoff);
+            let (accuracy_pct, resolved_count, accuracy_basis) = engine::category_accuracy(category, overall);
+
+            Some(SourceInput {
+                source_id: source.source_id.clone(),
+                source_name: source.source_name.clone(),
+                probability: point.probability,
+                accuracy_pct,
+                resolved_count,
+                volume: point.volume,
+                play_money: source.play_money,
+                accuracy_basis,
+                last_updated: Some(changed_at(&source.history, index)),
+                // Not recorded point-in-time, see the module docs
+                liquidity: None,
+                spread: None,
+                forecaster_count: None,
+                learned_weight: learned.and_then(|w| w.get(&source.source_id).copied()),
+                discovery_share: None,
+                dependence: Default::default(),
+                recalibration: None,
+            })
+        })
+        .collect()
+}
+
+/// When the probability at `index` was first quoted: polls that repeat an
+/// unchanged figure do not make a source fresh.
+fn changed_at(history: &[OddsPoint], index: usize) -> DateTime<Utc> {
+    let probability = history[index].probability;
+    let first = history[..index].iter()
+        .rposition(|p| p.probability != probability)
+        .map_or(0, |i| i + 1);
+    history[first].time
+}
+
+/// Accuracy from scores resolved before `cutoff` (and not before `since`), in
+/// `category` or overall when `None`.
+fn accuracy_as_of(
+    scores: &[PastScore],
+    source_id: &str,
+    category: Option<&str>,
+    since: Option<DateTime<Utc>>,
+    cutoff: DateTime<Utc>,
+) -> Option<AccuracyStats> {
+    let briers: Vec<f64> = scores.iter()
+        .filter(|s| s.source_id == source_id && s.resolved_at < cutoff)
+        .filter(|s| since.is_none_or(|since| s.resolved_at >= since))
+        .filter(|s| category.is_none() || s.category.as_deref() == category)
+        .map(|s| s.brier_score)
+        .collect();
+    if briers.is_empty() {
+        return None;
+    }
+    let mean = briers.iter().sum::<f64>() / briers.len() as f64;
+    Some(AccuracyStats {
+        accuracy_pct: (1.0 - mean) * 100.0,
+        resolved_count: briers.len() as i32,
+    })
+}
+
+fn summarize(name: &str, forecasts: &[(f64, f64)], consensus_brier_same_markets: Option<f64>) -> ScoreSummary {
+    ScoreSummary {
+        name: name.to_string(),
+        markets: forecasts.len(),
+        brier: mean_brier(forecasts),
+        log_score: mean_log_score(forecasts),
+        calibration: calibration::calibration_bins(forecasts, calibration::DEFAULT_BINS),
+        consensus_brier_same_markets,
+    }
+}
+
+fn mean_brier(forecasts: &[(f64, f64)]) -> f64 {
+    crate::brier::brier_score_average(forecasts).unwrap_or(f64::NAN)
+}
+
+/// Mean log probability assigned to the outcome.
+pub fn mean_log_score(forecasts: &[(f64, f64)]) -> f64 {
+    crate::scoring::log_score_average(forecasts).unwrap_or(f64::NAN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(time: DateTime<Utc>, probability: f64) -> OddsPoint {
+        OddsPoint { time, probability, volume: Some(1_000_000.0) }
+    }
+
+    fn source(id: &str, history: Vec<OddsPoint>) -> ReplaySource {
+        ReplaySource {
+            source_id: id.into(),
+            source_name: id.into(),
+            history,
+            play_money: false,
+        }
+    }
+
+    fn market(resolved_at: DateTime<Utc>, outcome: f64, sources: Vec<ReplaySource>) -> ReplayMarket {
+        ReplayMarket {
+            market_id: "m".into(),
+            title: "Test".into(),
+            category: None,
+            resolved_at,
+            outcome,
+            sources,
+        }
+    }
+
+    #[test]
+    fn test_horizon_parse() {
+        assert_eq!(Horizon::parse("7d"), Some(Horizon(Duration::days(7))));
+        assert_eq!(Horizon::parse("12h").unwrap().label(), "12h");
+        assert_eq!(Horizon::parse("2w").unwrap().label(), "14d");
+        assert!(Horizon::parse("d").is_none());
+        assert!(Horizon::parse("5x").is_none());
+    }
+
+    #[test]
+    fn test_replays_odds_at_horizon() {
+        let resolved = Utc::now();
+        let markets = vec![market(resolved, 1.0, vec![
+            source("a", vec![
+                point(resolved - Duration::days(10), 0.4),
+                point(resolved - Duration::hours(12), 0.9),
+            ]),
+            source("b", vec![point(resolved - Duration::days(2), 0.7)]),
+        ])];
+        let configs = vec![("default".to_string(), ConsensusConfig {
+            max_source_age_hours: 24.0 * 365.0,
+            ..ConsensusConfig::default()
+        })];
+        let horizons = [Horizon(Duration::days(1)), Horizon(Duration::days(7)), Horizon(Duration::days(30))];
+        let report = run_backtest(&markets, &[], &configs, &horizons);
+
+        // 1d: a quoted 0.4, b quoted 0.7
+        let one_day = &report.horizons[0];
+        assert_eq!(one_day.markets, 1);
+        let a = one_day.sources.iter().find(|s| s.name == "a").unwrap();
+        assert!((a.brier - 0.36).abs() < 1e-10);
+        assert_eq!(one_day.sources.len(), 2);
+
+        // 7d: only a had quoted
+        assert_eq!(report.horizons[1].sources.len(), 1);
+        // 30d: nothing quoted yet
+        assert_eq!(report.horizons[2].markets, 0);
+        assert_eq!(report.notes, vec![DEPTH_NOTE.to_string()]);
+    }
+
+    #[test]
+    fn test_accuracy_is_point_in_time() {
+        let now = Utc::now();
+        let scores = vec![
+            PastScore { source_id: "a".into(), category: None, brier_score: 0.1, resolved_at: now - Duration::days(5) },
+            PastScore { source_id: "a".into(), category: None, brier_score: 0.3, resolved_at: now + Duration::days(5) },
+        ];
+        let stats = accuracy_as_of(&scores, "a", None, None, now).unwrap();
+        assert_eq!(stats.resolved_count, 1);
+        assert!((stats.accuracy_pct - 90.0).abs() < 1e-10);
+        assert!(accuracy_as_of(&scores, "a", Some("politics"), None, now).is_none());
+        // Outside a 3-day window
+        assert!(accuracy_as_of(&scores, "a", None, Some(now - Duration::days(3)), now).is_none());
+    }
+
+    #[test]
+    fn test_learned_weights_are_fitted_out_of_time() {
+        let start = Utc::now() - Duration::days(200);
+        // One market a day: a is sharp, b always says 50%
+        let markets: Vec<ReplayMarket> = (0..100)
+            .map(|i| {
+                let resolved = start + Duration::days(i);
+                let outcome = if i % 3 == 0 { 0.0 } else { 1.0 };
+                let quoted = if outcome == 1.0 { 0.8 } else { 0.2 };
+                let opened = resolved - Duration::days(5);
+                market(resolved, outcome, vec![
+                    source("a", vec![point(opened, quoted)]),
+                    source("b", vec![point(opened, 0.5)]),
+                ])
+            })
+            .collect();
+        let history = WeightHistory::fit(&markets);
+
+        // Too few markets resolved before the first refit, none at all before it
+        assert!(history.as_of(start + Duration::days(10), None).is_none());
+        assert!(history.as_of(start + Duration::days(45), None).is_none());
+        let weights = history.as_of(start + Duration::days(65), None).unwrap();
+        assert!(weights["a"] > weights["b"]);
+        // No set for the category: the all-categories set applies
+        assert!(history.as_of(start + Duration::days(65), Some("politics")).is_some());
+
+        // Every fit only saw markets resolved before it
+        for (at, _) in &history.fits {
+            assert_eq!((*at - start).num_days() % WEIGHT_REFIT_DAYS, 0);
+        }
+    }
+
+    #[test]
+    fn test_repeated_polls_do_not_refresh_age() {
+        let now = Utc::now();
+        let history = vec![
+            point(now - Duration::days(5), 0.4),
+            point(now - Duration::days(3), 0.6),
+            point(now - Duration::days(2), 0.6),
+            point(now - Duration::hours(1), 0.6),
+        ];
+        assert_eq!(changed_at(&history, 3), now - Duration::days(3));
+        assert_eq!(changed_at(&history, 0), now - Duration::days(5));
+    }
+
+    #[test]
+    fn test_log_score() {
+        let forecasts = [(0.8, 1.0), (0.8, 0.0), (0.15, 0.0)];
+        let expected = (0.8f64.ln() + 0.2f64.ln() + 0.85f64.ln()) / 3.0;
+        assert!((mean_log_score(&forecasts) - expected).abs() < 1e-10);
+    }
+}
@@ -0,0 +1,292 @@
+//! Consensus backtest CLI.
+//!
+//! Replays odds history for resolved markets and scores the consensus at
+//! several horizons before resolution, side by side with every source.
+//!
+//! ```text
+//! backtest [--horizons 1d,7d,30d] [--category SLUG] [--json]
+//!          [--config NAME=JSON | --config NAME=@FILE]...
+//! ```
+//!
+//! Each `--config` is a consensus config as stored in `markets.consensus_config`.
+//! Without any, every aggregation method is compared under default settings,
+//! plus the default pool with learned weights (refitted out of time).
+
+use anyhow::{Context, Result, bail};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use sqlx::postgres::PgPoolOptions;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use forecise_consensus::aggregator::AggregationMethod;
+use forecise_consensus::backtest::{self, BacktestReport, Horizon, OddsPoint, PastScore, ReplayMarket, ReplaySource};
+use forecise_consensus::engine::ConsensusConfig;
+
+struct Args {
+    horizons: Vec<Horizon>,
+    configs: Vec<(String, ConsensusConfig)>,
+    category: Option<String>,
+    json: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = parse_args(std::env::args().skip(1))?;
+    let config = forecise_shared::Config::from_env()?;
+
+    let pool = PgPoolOptions::new()
+        .max_connections(2)
+        .connect(&config.database_url)
+        .await?;
+
+    let markets = load_markets(&pool, args.category.as_deref()).await?;
+    let scores = load_scores(&pool).await?;
+    eprintln!("Replaying {} resolved markets", markets.len());
+
+    let report = backtest::run_backtest(&markets, &scores, &args.configs, &args.horizons);
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report);
+    }
+
+    Ok(())
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args> {
+    let mut horizons = vec!["1d".to_string(), "7d".to_string(), "30d".to_string()];
+    let mut configs = Vec::new();
+    let mut category = None;
+    let mut json = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--horizons" => {
+                let value = args.next().context("--horizons needs a value")?;
+                horizons = value.split(',').map(str::to_string).collect();
+            }
+            "--config" => {
+                let value = args.next().context("--config needs NAME=JSON")?;
+                configs.push(parse_config(&value)?);
+            }
+            "--category" => category = Some(args.next().context("--category needs a slug")?),
+            "--json" => json = true,
+            other => bail!("Unknown argument: {}", other),
+        }
+    }
+
+    let horizons = horizons.iter()
+        .map(|h| Horizon::parse(h).with_context(|| format!("Invalid horizon: {}", h)))
+        .collect::<Result<Vec<_>>>()?;
+
+    if configs.is_empty() {
+        configs = [
+            AggregationMethod::LinearPool,
+            AggregationMethod::WeightedMedian,
+            AggregationMethod::LogOddsPool,
+            AggregationMethod::TrimmedMean { trim_fraction: 0.1 },
+        ]
+        .into_iter()
+        .map(|aggregation| {
+            let name = aggregation.aggregator().name().to_string();
+            (name, ConsensusConfig { aggregation, ..ConsensusConfig::default() })
+        })
+        .chain(std::iter::once((
+            "learned weights".to_string(),
+            ConsensusConfig { learned_weights: true, ..ConsensusConfig::default() },
+        )))
+        .collect();
+    }
+
+    Ok(Args { horizons, configs, category, json })
+}
+
+fn parse_config(value: &str) -> Result<(String, ConsensusConfig)> {
+    let (name, raw) = value.split_once('=').context("--config expects NAME=JSON or NAME=@FILE")?;
+    let raw = match raw.strip_prefix('@') {
+        Some(path) => std::fs::read_to_string(path).with_context(|| format!("Reading {}", path))?,
+        None => raw.to_string(),
+    };
+    let config: ConsensusConfig = serde_json::from_str(&raw).with_context(|| format!("Invalid config for {}", name))?;
+    config.validate().with_context(|| format!("Invalid config for {}", name))?;
+    Ok((name.to_string(), config))
+}
+
+async fn load_markets(pool: &PgPool, category: Option<&str>) -> Result<Vec<ReplayMarket>> {
+    #[derive(sqlx::FromRow)]
+    struct MarketRow {
+        id: Uuid,
+        title: String,
+        category: Option<String>,
+        outcome: BigDecimal,
+        resolved_at: DateTime<Utc>,
+    }
+
+    #[derive(sqlx::FromRow)]
+    struct SourceRow {
+        id: Uuid,
+        market_id: Uuid,
+        source_slug: String,
+        source_name: String,
+        play_money: bool,
+    }
+
+    #[derive(sqlx::FromRow)]
+    struct HistoryRow {
+        source_market_id: Uuid,
+        time: DateTime<Utc>,
+        probability: BigDecimal,
+        volume: Option<BigDecimal>,
+    }
+
+    let market_rows = sqlx::query_as::<_, MarketRow>(
+        r#"
+        SELECT
+            m.id,
+            m.title,
+            c.slug as category,
+            m.resolution_value as outcome,
+            COALESCE(m.resolution_date, m.updated_at) as resolved_at
+        FROM markets m
+        LEFT JOIN categories c ON m.category_id = c.id
+        WHERE m.status = 'resolved'
+        AND m.resolution_value IS NOT NULL
+        AND ($1::text IS NULL OR c.slug = $1)
+        "#
+    )
+    .bind(category)
+    .fetch_all(pool)
+    .await?;
+
+    let market_ids: Vec<Uuid> = market_rows.iter().map(|m| m.id).collect();
+
+    let source_rows = sqlx::query_as::<_, SourceRow>(
+        r#"
+        SELECT
+            sm.id,
+            sm.market_id,
+            s.slug as source_slug,
+            s.name as source_name,
+            s.play_money
+        FROM source_markets sm
+        JOIN sources s ON sm.source_id = s.id
+        WHERE sm.market_id = ANY($1)
+        "#
+    )
+    .bind(&market_ids)
+    .fetch_all(pool)
+    .await?;
+
+    let source_market_ids: Vec<Uuid> = source_rows.iter().map(|s| s.id).collect();
+
+    let history_rows = sqlx::query_as::<_, HistoryRow>(
+        r#"
+        SELECT source_market_id, time, probability, to_usd(volume, currency) as volume
+        FROM odds_history
+        WHERE source_market_id = ANY($1)
+        ORDER BY time
+        "#
+    )
+    .bind(&source_market_ids)
+    .fetch_all(pool)
+    .await?;
+
+    let mut history: HashMap<Uuid, Vec<OddsPoint>> = HashMap::new();
+    for row in history_rows {
+        history.entry(row.source_market_id).or_default().push(OddsPoint {
+            time: row.time,
+            probability: to_f64(&row.probability),
+            volume: row.volume.as_ref().map(to_f64),
+        });
+    }
+
+    let mut sources: HashMap<Uuid, Vec<ReplaySource>> = HashMap::new();
+    for row in source_rows {
+        sources.entry(row.market_id).or_default().push(ReplaySource {
+            source_id: row.source_slug,
+            source_name: row.source_name,
+            history: history.remove(&row.id).unwrap_or_default(),
+            play_money: row.play_money,
+        });
+    }
+
+    Ok(market_rows.into_iter()
+        .map(|m| ReplayMarket {
+            market_id: m.id.to_string(),
+            title: m.title,
+            category: m.category,
+            resolved_at: m.resolved_at,
+            outcome: to_f64(&m.outcome),
+            sources: sources.remove(&m.id).unwrap_or_default(),
+        })
+        .collect())
+}
+
+async fn load_scores(pool: &PgPool) -> Result<Vec<PastScore>> {
+    #[derive(sqlx::FromRow)]
+    struct Row {
+        source_slug: String,
+        category: Option<String>,
+        brier_score: BigDecimal,
+        resolved_at: DateTime<Utc>,
+    }
+
+    let rows = sqlx::query_as::<_, Row>(
+        r#"
+        SELECT s.slug as source_slug, c.slug as category, ps.brier_score, ps.resolved_at
+        FROM prediction_scores ps
+        JOIN sources s ON ps.source_id = s.id
+        LEFT JOIN categories c ON ps.category_id = c.id
+        WHERE ps.horizon = 'final'
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter()
+        .map(|r| PastScore {
+            source_id: r.source_slug,
+            category: r.category,
+            brier_score: to_f64(&r.brier_score),
+            resolved_at: r.resolved_at,
+        })
+        .collect())
+}
+
+fn to_f64(value: &BigDecimal) -> f64 {
+    value.to_string().parse().unwrap_or(0.0)
+}
+
+fn print_report(report: &BacktestReport) {
+    for horizon in &report.horizons {
+        println!("\n== {} before resolution ({} markets) ==", horizon.horizon, horizon.markets);
+        println!("{:<28} {:>8} {:>10} {:>10} {:>14}", "", "markets", "brier", "log score", "consensus*");
+        for s in &horizon.configs {
+            println!("{:<28} {:>8} {:>10.4} {:>10.4} {:>14}", s.name, s.markets, s.brier, s.log_score, "");
+        }
+        for s in &horizon.sources {
+            let reference = s.consensus_brier_same_markets
+                .map(|b| format!("{:.4}", b))
+                .unwrap_or_default();
+            println!("{:<28} {:>8} {:>10.4} {:>10.4} {:>14}", s.name, s.markets, s.brier, s.log_score, reference);
+        }
+        println!("* Brier of the first configuration on the markets the source covered");
+
+        for s in &horizon.configs {
+            println!("\nCalibration: {}", s.name);
+            for bin in &s.calibration {
+                println!(
+                    "  {:.1}-{:.1}  n={:<5} forecast {:.3}  observed {:.3}",
+                    bin.lower, bin.upper, bin.count, bin.mean_forecast, bin.observed_rate
+                );
+            }
+        }
+    }
+
+    for note in &report.notes {
+        println!("\nNote: {}", note);
+    }
+}
@@ -29,7 +29,8 @@ pub fn brier_score_average(predictions: &[(f64, f64)]) -> Option<f64> {
 
 /// Convert Brier Score to an accuracy percentage (0-100%).
 /// Uses a calibrated transformation: accuracy = (1 - brier_score) * 100
-/// A Brier Score of 0.25 (random guessing on binary) = 75% accuracy
+/// A Brier Score of 0.25 (random guessing on binary) = 75% accuracy, so
+/// compare sources by [`crate::scoring::brier_skill_score`] instead.
 pub fn brier_to_accuracy_pct(brier_score: f64) -> f64 {
     ((1.0 - brier_score) * 100.0).clamp(0.0, 100.0)
 }
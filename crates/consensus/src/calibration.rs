@@ -0,0 +1,130 @@
+//! Calibration analysis.
+//!
+//! Predictions are bucketed by forecast probability and each bucket's mean
+//! forecast is compared with how often the event actually happened. The
+//! Murphy decomposition splits the Brier score into
+//! `reliability - resolution + uncertainty`: reliability is the calibration
+//! error (lower is better), resolution how far bucket outcomes move away from
+//! the base rate (higher is better), and uncertainty the base rate variance,
+//! which no forecaster can change.
+
+use serde::{Deserialize, Serialize};
+
+/// Default number of equal-width bins.
+pub const DEFAULT_BINS: usize = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibrationBin {
+    pub lower: f64,
+    pub upper: f64,
+    pub count: usize,
+    pub mean_forecast: f64,
+    pub observed_rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibrationReport {
+    pub sample_size: usize,
+    pub brier_score: f64,
+    /// Count-weighted squared gap between forecast and observed rate (lower is better).
+    pub reliability: f64,
+    /// Count-weighted squared distance of observed rates from the base rate (higher is better).
+    pub resolution: f64,
+    /// `base_rate * (1 - base_rate)`.
+    pub uncertainty: f64,
+    /// Count-weighted absolute gap between forecast and observed rate.
+    pub expected_calibration_error: f64,
+    pub base_rate: f64,
+    /// Non-empty bins, lowest first.
+    pub bins: Vec<CalibrationBin>,
+}
+
+/// Bucket `(forecast, outcome)` pairs into `n_bins` equal-width bins.
+/// Empty bins are left out.
+pub fn calibration_bins(forecasts: &[(f64, f64)], n_bins: usize) -> Vec<CalibrationBin> {
+    let n_bins = n_bins.max(1);
+    let mut bins = vec![(0usize, 0.0, 0.0); n_bins];
+    for (p, y) in forecasts {
+        let i = ((p.clamp(0.0, 1.0) * n_bins as f64) as usize).min(n_bins - 1);
+        bins[i].0 += 1;
+        bins[i].1 += p;
+        bins[i].2 += y;
+    }
+    bins.into_iter()
+        .enumerate()
+        .filter(|(_, (count, _, _))| *count > 0)
+        .map(|(i, (count, sum_p, sum_y))| CalibrationBin {
+            lower: i as f64 / n_bins as f64,
+            upper: (i + 1) as f64 / n_bins as f64,
+            count,
+            mean_forecast: sum_p / count as f64,
+            observed_rate: sum_y / count as f64,
+        })
+        .collect()
+}
+
+/// Full calibration report, or `None` without predictions.
+///
+/// The decomposition is computed on the binned forecasts, so
+/// `reliability - resolution + uncertainty` equals the Brier score only up to
+/// the spread of forecasts within each bin.
+pub fn calibration_report(forecasts: &[(f64, f64)], n_bins: usize) -> Option<CalibrationReport> {
+    if forecasts.is_empty() {
+        return None;
+    }
+    let n = forecasts.len() as f64;
+    let base_rate = forecasts.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let brier_score = crate::brier::brier_score_average(forecasts)?;
+    let bins = calibration_bins(forecasts, n_bins);
+
+    let weighted = |f: &dyn Fn(&CalibrationBin) -> f64| {
+        bins.iter().map(|b| b.count as f64 * f(b)).sum::<f64>() / n
+    };
+
+    Some(CalibrationReport {
+        sample_size: forecasts.len(),
+        brier_score,
+        reliability: weighted(&|b| (b.mean_forecast - b.observed_rate).powi(2)),
+        resolution: weighted(&|b| (b.observed_rate - base_rate).powi(2)),
+        uncertainty: base_rate * (1.0 - base_rate),
+        expected_calibration_error: weighted(&|b| (b.mean_forecast - b.observed_rate).abs()),
+        base_rate,
+        bins,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bins() {
+        let bins = calibration_bins(&[(0.8, 1.0), (0.8, 0.0), (0.15, 0.0), (1.0, 1.0)], 10);
+        assert_eq!(bins.len(), 3);
+        assert_eq!(bins[1].count, 2);
+        assert!((bins[1].observed_rate - 0.5).abs() < 1e-10);
+        // 1.0 falls in the top bin
+        assert!((bins[2].lower - 0.9).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_decomposition_matches_brier_when_bins_are_pure() {
+        // Longshots priced at 0.2 that only hit 10% of the time, favourites at 0.8 hitting 80%
+        let mut forecasts = Vec::new();
+        for i in 0..10 {
+            forecasts.push((0.2, if i == 0 { 1.0 } else { 0.0 }));
+            forecasts.push((0.8, if i < 8 { 1.0 } else { 0.0 }));
+        }
+        let report = calibration_report(&forecasts, 10).unwrap();
+        let decomposed = report.reliability - report.resolution + report.uncertainty;
+        assert!((decomposed - report.brier_score).abs() < 1e-10);
+        assert!((report.reliability - 0.005).abs() < 1e-10);
+        assert!((report.expected_calibration_error - 0.05).abs() < 1e-10);
+        assert!((report.base_rate - 0.45).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_empty() {
+        assert!(calibration_report(&[], 10).is_none());
+    }
+}
@@ -0,0 +1,245 @@
+//! Head-to-head comparison of two forecasters on the same questions.
+//!
+//! Scores are paired by market, so question difficulty cancels out. The mean
+//! of the per-market differences `a - b` gets a Student-t confidence interval
+//! and a two-sided paired t-test against "no difference".
+
+use serde::{Deserialize, Serialize};
+
+/// Default confidence level of the interval.
+pub const DEFAULT_COMPARISON_LEVEL: f64 = 0.95;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairedComparison {
+    pub markets: usize,
+    pub mean_a: f64,
+    pub mean_b: f64,
+    /// Mean of `a - b`.
+    pub mean_difference: f64,
+    pub std_error: f64,
+    pub ci_lower: f64,
+    pub ci_upper: f64,
+    pub level: f64,
+    pub t_statistic: f64,
+    /// Two-sided p-value of the paired t-test.
+    pub p_value: f64,
+    /// Markets where `a` scored higher, lower, or the same as `b`.
+    pub a_higher: usize,
+    pub b_higher: usize,
+    pub ties: usize,
+}
+
+impl PairedComparison {
+    /// Whether the interval excludes zero.
+    pub fn is_significant(&self) -> bool {
+        self.ci_lower > 0.0 || self.ci_upper < 0.0
+    }
+}
+
+/// Compare paired `(a, b)` scores. `None` with fewer than two pairs.
+pub fn compare_paired(pairs: &[(f64, f64)], level: f64) -> Option<PairedComparison> {
+    let n = pairs.len();
+    if n < 2 {
+        return None;
+    }
+    let nf = n as f64;
+    let diffs: Vec<f64> = pairs.iter().map(|(a, b)| a - b).collect();
+    let mean_difference = diffs.iter().sum::<f64>() / nf;
+    let variance = diffs.iter().map(|d| (d - mean_difference).powi(2)).sum::<f64>() / (nf - 1.0);
+    let std_error = (variance / nf).sqrt();
+    let df = nf - 1.0;
+
+    let (t_statistic, p_value) = if std_error > 0.0 {
+        let t = mean_difference / std_error;
+        (t, t_two_sided_p(t, df))
+    } else if mean_difference == 0.0 {
+        (0.0, 1.0)
+    } else {
+        // Every market moved by exactly the same amount
+        (mean_difference.signum() * f64::MAX, 0.0)
+    };
+    let half_width = t_critical(level, df) * std_error;
+
+    Some(PairedComparison {
+        markets: n,
+        mean_a: pairs.iter().map(|(a, _)| a).sum::<f64>() / nf,
+        mean_b: pairs.iter().map(|(_, b)| b).sum::<f64>() / nf,
+        mean_difference,
+        std_error,
+        ci_lower: mean_difference - half_width,
+        ci_upper: mean_difference + half_width,
+        level,
+        t_statistic,
+        p_value,
+        a_higher: diffs.iter().filter(|d| **d > 0.0).count(),
+        b_higher: diffs.iter().filter(|d| **d < 0.0).count(),
+        ties: diffs.iter().filter(|d| **d == 0.0).count(),
+    })
+}
+
+/// Rank entries by their `(lower, upper)` confidence intervals. An entry's
+/// rank is one plus the number of entries whose whole interval is better, so
+/// entries that cannot be told apart share a rank. Also returns whether each
+/// entry's interval overlaps another's, i.e. whether it is tied with anyone.
+pub fn rank_with_ties(intervals: &[(f64, f64)], higher_is_better: bool) -> Vec<(usize, bool)> {
+    let better = |other: &(f64, f64), this: &(f64, f64)| {
+        if higher_is_better { other.0 > this.1 } else { other.1 < this.0 }
+    };
+    let ranks: Vec<usize> = intervals.iter()
+        .map(|this| 1 + intervals.iter().filter(|other| better(other, this)).count())
+        .collect();
+    ranks.into_iter()
+        .zip(intervals)
+        .enumerate()
+        .map(|(i, (rank, this))| {
+            let tied = intervals.iter()
+                .enumerate()
+                .any(|(j, other)| j != i && other.0 <= this.1 && this.0 <= other.1);
+            (rank, tied)
+        })
+        .collect()
+}
+
+/// `P(|T| > |t|)` for Student's t with `df` degrees of freedom.
+fn t_two_sided_p(t: f64, df: f64) -> f64 {
+    incomplete_beta(df / (df + t * t), df / 2.0, 0.5).clamp(0.0, 1.0)
+}
+
+/// Critical value `t` with `P(|T| > t) = 1 - level`, by bisection.
+pub fn t_critical(level: f64, df: f64) -> f64 {
+    let alpha = 1.0 - level.clamp(0.5, 0.9999);
+    let (mut lo, mut hi) = (0.0, 1000.0);
+    for _ in 0..100 {
+        let mid = (lo + hi) / 2.0;
+        if t_two_sided_p(mid, df) > alpha {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    (lo + hi) / 2.0
+}
+
+/// Regularized incomplete beta function `I_x(a, b)`.
+fn incomplete_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let front = (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    // The continued fraction converges quickly only below the mean
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(x, a, b) / a
+    } else {
+        1.0 - front * beta_continued_fraction(1.0 - x, b, a) / b
+    }
+}
+
+/// Lentz's method for the incomplete beta continued fraction.
+fn beta_continued_fraction(x: f64, a: f64, b: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+    for m in 1..300 {
+        let m = m as f64;
+        for numerator in [
+            m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m)),
+            -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0)),
+        ] {
+            d = 1.0 + numerator * d;
+            if d.abs() < TINY {
+                d = TINY;
+            }
+            c = 1.0 + numerator / c;
+            if c.abs() < TINY {
+                c = TINY;
+            }
+            d = 1.0 / d;
+            h *= d * c;
+        }
+        if (d * c - 1.0).abs() < 1e-14 {
+            break;
+        }
+    }
+    h
+}
+
+/// Lanczos approximation of `ln Γ(x)` for `x > 0`.
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.18009172947146,
+        -86.50532032941677,
+        24.01409824083091,
+        -1.231739572450155,
+        0.1208650973866179e-2,
+        -0.5395239384953e-5,
+    ];
+    let tmp = x + 5.5 - (x + 0.5) * (x + 5.5).ln();
+    let mut series = 1.000000000190015;
+    for (i, c) in COEFFICIENTS.iter().enumerate() {
+        series += c / (x + 1.0 + i as f64);
+    }
+    -tmp + (2.5066282746310005 * series / x).ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_t_distribution() {
+        // Standard table values
+        assert!((t_critical(0.95, 10.0) - 2.228).abs() < 1e-3);
+        assert!((t_critical(0.95, 1000.0) - 1.962).abs() < 1e-3);
+        assert!((t_two_sided_p(2.0, 20.0) - 0.0593).abs() < 1e-3);
+        assert!((t_two_sided_p(0.0, 5.0) - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_clear_difference_is_significant() {
+        let pairs: Vec<(f64, f64)> = (0..40)
+            .map(|i| (0.20 + 0.01 * (i % 5) as f64, 0.10 + 0.01 * (i % 3) as f64))
+            .collect();
+        let c = compare_paired(&pairs, DEFAULT_COMPARISON_LEVEL).unwrap();
+        assert!(c.mean_difference > 0.08);
+        assert!(c.is_significant());
+        assert!(c.p_value < 1e-6);
+        assert_eq!(c.a_higher, 40);
+    }
+
+    #[test]
+    fn test_rank_with_ties() {
+        // Two leaders that cannot be separated, then a clearly worse third
+        let intervals = [(0.70, 0.80), (0.72, 0.78), (0.40, 0.50)];
+        assert_eq!(rank_with_ties(&intervals, true), vec![(1, true), (1, true), (3, false)]);
+        // Lower is better: the third is now alone at the top
+        assert_eq!(rank_with_ties(&intervals, false), vec![(2, true), (2, true), (1, false)]);
+
+        // The third is alone at its rank but still tied with the second it
+        // overlaps, and a wide interval can outrank a higher but tighter one
+        let intervals = [(0.70, 0.80), (0.65, 0.72), (0.60, 0.66)];
+        assert_eq!(rank_with_ties(&intervals, true), vec![(1, true), (1, true), (2, true)]);
+        let intervals = [(0.89, 0.91), (0.84, 0.86), (0.10, 1.10)];
+        assert_eq!(rank_with_ties(&intervals, true), vec![(1, true), (2, true), (1, true)]);
+        // Separate intervals are never tied
+        let intervals = [(0.80, 0.90), (0.60, 0.70)];
+        assert_eq!(rank_with_ties(&intervals, true), vec![(1, false), (2, false)]);
+    }
+
+    #[test]
+    fn test_noise_is_not_significant() {
+        let pairs = [(0.1, 0.2), (0.2, 0.1), (0.15, 0.1), (0.1, 0.15)];
+        let c = compare_paired(&pairs, DEFAULT_COMPARISON_LEVEL).unwrap();
+        assert!(c.mean_difference.abs() < 1e-10);
+        assert!(!c.is_significant());
+        assert!(c.p_value > 0.9);
+        assert!(compare_paired(&[(0.1, 0.2)], 0.95).is_none());
+    }
+}
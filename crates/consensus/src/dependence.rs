@@ -0,0 +1,174 @@
+//! Correction for sources that copy each other.
+//!
+//! Weighting assumes each source is an independent opinion. When one venue
+//! mostly tracks another, the shared opinion is counted twice. Pairwise
+//! dependence (0 = independent, 1 = the same opinion) is estimated from how
+//! closely the sources' odds move together and how correlated their
+//! residuals are on resolved markets. Each source's weight is then divided by
+//! `1 + Σ dependence` on the other sources present: a group of `n` copies
+//! carries the weight of one, while a dependence shared equally by every
+//! source leaves the relative weights alone.
+
+use std::collections::BTreeMap;
+
+use crate::leadlag::pearson;
+
+/// Fewest shared resolved markets for a residual correlation to be used.
+pub const MIN_SHARED_MARKETS: i64 = 10;
+
+/// Scored horizon residuals are taken at. Far enough out that the outcome is
+/// not yet priced in.
+pub const RESIDUAL_HORIZON: &str = "7d";
+
+/// Correlation of each pair's residuals on the markets both quote, with the
+/// number of those markets, for pairs sharing at least
+/// [`MIN_SHARED_MARKETS`]. `markets` holds each resolved market's quotes by
+/// source. A residual is the source's probability minus the mean of the other
+/// sources on that market, so the outcome they all forecast cancels out and
+/// only a shared opinion correlates; markets quoted by fewer than three
+/// sources have no outside reference and are skipped. Pairs are keyed
+/// lower id first.
+pub fn residual_correlations<K: Ord + Clone>(markets: &[Vec<(K, f64)>]) -> BTreeMap<(K, K), (Option<f64>, i64)> {
+    let mut residuals: BTreeMap<(K, K), (Vec<f64>, Vec<f64>)> = BTreeMap::new();
+    for quotes in markets.iter().filter(|quotes| quotes.len() >= 3) {
+        let others = (quotes.len() - 1) as f64;
+        let total: f64 = quotes.iter().map(|(_, p)| p).sum();
+        let residual = |p: f64| p - (total - p) / others;
+        for (i, (a, pa)) in quotes.iter().enumerate() {
+            for (b, pb) in &quotes[i + 1..] {
+                let (key, ra, rb) = if a < b {
+                    ((a.clone(), b.clone()), residual(*pa), residual(*pb))
+                } else {
+                    ((b.clone(), a.clone()), residual(*pb), residual(*pa))
+                };
+                let (xs, ys) = residuals.entry(key).or_default();
+                xs.push(ra);
+                ys.push(rb);
+            }
+        }
+    }
+    residuals.into_iter()
+        .filter(|(_, (xs, _))| xs.len() as i64 >= MIN_SHARED_MARKETS)
+        .map(|(key, (xs, ys))| (key, (pearson(&xs, &ys), xs.len() as i64)))
+        .collect()
+}
+
+/// Pairwise dependence from co-movement of odds changes and correlation of
+/// residuals on shared resolved markets: the mean of whichever are known,
+/// negative correlations counting as independent.
+pub fn combine(comovement: Option<f64>, residual_correlation: Option<f64>) -> Option<f64> {
+    let known: Vec<f64> = [comovement, residual_correlation]
+        .into_iter()
+        .flatten()
+        .filter(|r| r.is_finite())
+        .map(|r| r.clamp(0.0, 1.0))
+        .collect();
+    if known.is_empty() {
+        return None;
+    }
+    Some(known.iter().sum::<f64>() / known.len() as f64)
+}
+
+/// Weight multiplier for each source, given each one's dependence on the
+/// others by source id. Dependence on sources not in `ids` is ignored.
+pub fn dependence_factors(ids: &[&str], dependence: &[&BTreeMap<String, f64>]) -> Vec<f64> {
+    ids.iter()
+        .enumerate()
+        .map(|(i, id)| {
+            let shared: f64 = ids.iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(j, other)| pairwise(dependence[i], other).max(pairwise(dependence[j], id)))
+                .sum();
+            1.0 / (1.0 + shared)
+        })
+        .collect()
+}
+
+fn pairwise(dependence: &BTreeMap<String, f64>, other: &str) -> f64 {
+    dependence.get(other).copied().unwrap_or(0.0).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(pairs: &[(&str, f64)]) -> BTreeMap<String, f64> {
+        pairs.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+    }
+
+    #[test]
+    fn test_copies_count_once() {
+        // Three venues quoting one opinion, one independent venue
+        let pm = map(&[("manifold", 1.0), ("metaculus", 1.0)]);
+        let mf = map(&[("polymarket", 1.0), ("metaculus", 1.0)]);
+        let mc = map(&[("polymarket", 1.0), ("manifold", 1.0)]);
+        let other = BTreeMap::new();
+        let factors = dependence_factors(
+            &["polymarket", "manifold", "metaculus", "kalshi"],
+            &[&pm, &mf, &mc, &other],
+        );
+        for f in &factors[..3] {
+            assert!((f - 1.0 / 3.0).abs() < 1e-10);
+        }
+        assert_eq!(factors[3], 1.0);
+        // The group together weighs as much as the independent venue
+        assert!((factors[..3].iter().sum::<f64>() - factors[3]).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_one_sided_and_absent_dependence() {
+        // Only one side recorded: applies to both
+        let a = map(&[("b", 0.5), ("gone", 1.0)]);
+        let b = BTreeMap::new();
+        let factors = dependence_factors(&["a", "b"], &[&a, &b]);
+        assert!((factors[0] - 1.0 / 1.5).abs() < 1e-10);
+        assert!((factors[1] - 1.0 / 1.5).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_residual_correlations() {
+        // Sources quoting the same truth with their own noise, plus a copy of "a"
+        let mut state = 7u64;
+        let mut noise = || {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            ((state >> 33) as f64 / (1u64 << 31) as f64 - 0.5) * 0.2
+        };
+        let markets: Vec<Vec<(&str, f64)>> = (0..200)
+            .map(|i| {
+                let truth = 0.1 + 0.8 * (i % 17) as f64 / 16.0;
+                let a = truth + noise();
+                vec![
+                    ("a", a),
+                    ("b", truth + noise()),
+                    ("c", truth + noise()),
+                    ("d", truth + noise()),
+                    ("copy", a),
+                ]
+            })
+            .collect();
+
+        let without_copy: Vec<Vec<(&str, f64)>> = markets.iter().map(|m| m[..4].to_vec()).collect();
+        let (r, shared) = residual_correlations(&without_copy)[&("a", "b")];
+        assert_eq!(shared, 200);
+        // The shared truth does not make independent sources look dependent
+        assert!(combine(None, r).unwrap() < 0.1);
+
+        let with_copy = residual_correlations(&markets);
+        assert!(with_copy[&("a", "copy")].0.unwrap() > 0.99);
+        assert!(combine(None, with_copy[&("b", "c")].0).unwrap() < 0.1);
+
+        // Too few markets, or too few sources per market
+        assert!(residual_correlations(&without_copy[..5]).is_empty());
+        let pairs_only: Vec<Vec<(&str, f64)>> = markets.iter().map(|m| m[..2].to_vec()).collect();
+        assert!(residual_correlations(&pairs_only).is_empty());
+    }
+
+    #[test]
+    fn test_combine() {
+        assert_eq!(combine(None, None), None);
+        assert_eq!(combine(Some(0.8), None), Some(0.8));
+        assert!((combine(Some(0.8), Some(-0.2)).unwrap() - 0.4).abs() < 1e-10);
+        assert_eq!(combine(Some(f64::NAN), Some(1.5)), Some(1.0));
+    }
+}
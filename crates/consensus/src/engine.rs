@@ -6,18 +6,88 @@
 
 use anyhow::Result;
 use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
+use crate::aggregator::{AggregationMethod, Aggregator};
+use crate::dependence;
+use crate::extremize::{self, DEFAULT_EXTREMIZING_EXPONENT};
+use crate::interval::{self, DEFAULT_INTERVAL_LEVEL};
+use crate::liquidity::{self, LiquidityBreakdown};
+use crate::outlier::{self, OutlierMode, OutlierReference};
+use crate::recalibration::Recalibration;
+
 /// A source's input to the consensus calculation.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SourceInput {
     pub source_id: String,
     pub source_name: String,
     pub probability: f64,
     pub accuracy_pct: Option<f64>,
     pub resolved_count: i32,
+    /// Traded volume in USD. `None` when unknown or in a currency without a
+    /// USD rate, such as play money.
     pub volume: Option<f64>,
+    /// Whether the source trades in a currency with no cash value. Its depth
+    /// is then weighted by `play_money_liquidity_factor` when the volume and
+    /// liquidity could not be converted to USD.
+    #[serde(default)]
+    pub play_money: bool,
+    /// Which accuracy record `accuracy_pct` and `resolved_count` come from.
+    #[serde(default)]
+    pub accuracy_basis: AccuracyBasis,
+    /// When the source's probability last changed. `None` is treated as current.
+    #[serde(default)]
+    pub last_updated: Option<DateTime<Utc>>,
+    /// Order book or AMM liquidity behind the market, in USD like `volume`.
+    #[serde(default)]
+    pub liquidity: Option<f64>,
+    /// Bid-ask spread in probability points.
+    #[serde(default)]
+    pub spread: Option<f64>,
+    /// Number of forecasters, for platforms without a market (e.g. Metaculus).
+    #[serde(default)]
+    pub forecaster_count: Option<i64>,
+    /// Weight from the active learned weight set, if the source is in it.
+    #[serde(default)]
+    pub learned_weight: Option<f64>,
+    /// Share of price discovery against the other venues (0.5 = neither
+    /// leads), from lead-lag analysis of odds histories.
+    #[serde(default)]
+    pub discovery_share: Option<f64>,
+    /// Estimated dependence (0 = independent, 1 = the same opinion) on other
+    /// sources, by source id.
+    #[serde(default)]
+    pub dependence: BTreeMap<String, f64>,
+    /// Correction for the source's miscalibration, fitted on its resolved
+    /// forecasts.
+    #[serde(default)]
+    pub recalibration: Option<Recalibration>,
+}
+
+/// Which accuracy record a source's weight was derived from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccuracyBasis {
+    /// The source's record in the market's category, used as-is: it has no
+    /// resolved questions outside the category to shrink toward.
+    Category,
+    /// The category record shrunk toward the source's overall record.
+    CategoryShrunk,
+    /// The source's overall record (no record in this category).
+    Overall,
+    /// No accuracy record yet.
+    #[default]
+    None,
+}
+
+/// An accuracy record as loaded from `accuracy_records`.
+#[derive(Debug, Clone, Copy)]
+pub struct AccuracyStats {
+    pub accuracy_pct: f64,
+    pub resolved_count: i32,
 }
 
 /// The result of a consensus calculation.
@@ -29,21 +99,78 @@ pub struct ConsensusResult {
     pub confidence: f64,
     /// Agreement score (0-1), how much sources agree with each other.
     pub agreement: f64,
+    /// Lower bound of the bootstrap interval around `probability`.
+    pub interval_lower: f64,
+    /// Upper bound of the bootstrap interval around `probability`.
+    pub interval_upper: f64,
     /// Number of sources used.
     pub source_count: usize,
     /// Weights assigned to each source.
     pub weights: Vec<SourceWeight>,
-    /// Sources that are outliers (>15% from consensus).
+    /// Sources flagged as outliers, and what was done about them.
     pub outliers: Vec<OutlierSource>,
+    /// Name of the aggregation method that produced `probability`.
+    pub method: String,
+    /// Log-odds pool of the same weighted inputs, scaled by `extremizing_exponent`.
+    pub extremized_probability: f64,
+    /// Exponent applied to produce `extremized_probability`.
+    pub extremizing_exponent: f64,
+    /// Sources left out because their last update is older than the cutoff.
+    pub stale_sources: Vec<StaleSource>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SourceWeight {
     pub source_id: String,
     pub source_name: String,
+    /// Probability pooled, after recalibration.
     pub probability: f64,
+    /// Probability as quoted, when recalibration changed it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_probability: Option<f64>,
     pub weight: f64,
     pub accuracy_pct: Option<f64>,
+    #[serde(default)]
+    pub accuracy_basis: AccuracyBasis,
+    /// Resolved questions behind `accuracy_pct`.
+    #[serde(default)]
+    pub resolved_count: i32,
+    /// Hours since the source's probability was last changed.
+    #[serde(default)]
+    pub age_hours: Option<f64>,
+    /// Time-decay multiplier applied to the weight (1 = fresh).
+    #[serde(default = "neutral_factor")]
+    pub staleness_factor: f64,
+    /// Depth multiplier applied to the weight and what it was built from.
+    #[serde(default)]
+    pub liquidity: LiquidityBreakdown,
+    /// Learned weight the base weight came from, when one was used.
+    #[serde(default)]
+    pub learned_weight: Option<f64>,
+    /// Outlier multiplier applied to the weight (1 = not an outlier, or report only).
+    #[serde(default = "neutral_factor")]
+    pub outlier_factor: f64,
+    /// Price discovery multiplier applied to the weight (1 = off or neutral).
+    #[serde(default = "neutral_factor")]
+    pub discovery_factor: f64,
+    /// Multiplier for overlap with other sources in the consensus (1 = independent).
+    #[serde(default = "neutral_factor")]
+    pub dependence_factor: f64,
+    /// Dependence on the other sources in the consensus it was built from.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub dependent_on: BTreeMap<String, f64>,
+}
+
+fn neutral_factor() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaleSource {
+    pub source_id: String,
+    pub source_name: String,
+    pub probability: f64,
+    pub age_hours: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,81 +178,410 @@ pub struct OutlierSource {
     pub source_id: String,
     pub source_name: String,
     pub probability: f64,
+    /// Distance from the reference it was checked against.
     pub deviation: f64,
+    #[serde(default)]
+    pub reference: f64,
+    /// Deviation in MAD-scaled units.
+    #[serde(default)]
+    pub robust_z: f64,
+    #[serde(default)]
+    pub action: OutlierMode,
+    #[serde(default)]
+    pub reason: String,
 }
 
 /// Minimum resolved questions for a source to get accuracy-based weighting.
-const MIN_RESOLVED_FOR_ACCURACY: i32 = 30;
+pub const MIN_RESOLVED_FOR_ACCURACY: i32 = 30;
+
+/// Minimum deviation (probability points) for a source to be an outlier.
+pub const OUTLIER_THRESHOLD: f64 = 0.05;
+
+/// Deviation, in scaled MADs, beyond which a source is an outlier.
+pub const OUTLIER_MAD_THRESHOLD: f64 = 3.0;
+
+/// Age at which a source's weight is halved.
+pub const STALENESS_HALF_LIFE_HOURS: f64 = 24.0;
+
+/// Sources older than this are left out of the consensus.
+pub const MAX_SOURCE_AGE_HOURS: f64 = 72.0;
+
+/// Weight multiplier for the thinnest markets.
+pub const MIN_LIQUIDITY_FACTOR: f64 = 0.2;
+
+/// Rolling windows, in days, that accuracy records are kept for besides all time.
+pub const ACCURACY_WINDOWS_DAYS: [i32; 2] = [90, 365];
+
+/// Per-market consensus settings, stored as JSON in `markets.consensus_config`.
+/// Missing fields fall back to the defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConsensusConfig {
+    /// How weighted source probabilities are pooled.
+    pub aggregation: AggregationMethod,
+    /// Minimum resolved questions for a source to get accuracy-based weighting.
+    /// Not used when learned weights replace the heuristic.
+    pub min_resolved_for_accuracy: i32,
+    /// Minimum deviation (probability points) for a source to be an outlier.
+    pub outlier_threshold: f64,
+    /// Deviation, in scaled MADs, beyond which a source is an outlier.
+    pub outlier_mad_threshold: f64,
+    /// What outliers are compared against.
+    pub outlier_reference: OutlierReference,
+    /// Whether outliers are only reported, down-weighted or excluded.
+    pub outlier_mode: OutlierMode,
+    /// Exponent for the extremized consensus. `None` uses the default.
+    pub extremizing_exponent: Option<f64>,
+    /// Age at which a source's weight is halved.
+    pub staleness_half_life_hours: f64,
+    /// Sources older than this are excluded and reported as stale.
+    pub max_source_age_hours: f64,
+    /// Coverage of the uncertainty interval (e.g. 0.9 for 5th-95th percentile).
+    pub interval_level: f64,
+    /// Scale weights by market depth (volume, liquidity, spread, forecasters).
+    pub liquidity_weighting: bool,
+    /// Multiplier given to the thinnest markets when liquidity weighting is on.
+    pub min_liquidity_factor: f64,
+    /// Depth multiplier for play-money sources whose volume and liquidity
+    /// have no USD value.
+    pub play_money_liquidity_factor: f64,
+    /// Use learned source weights instead of the accuracy heuristic when the
+    /// sources have them. Off by default: they replace the heuristic outright,
+    /// so `min_resolved_for_accuracy`, `accuracy_window_days`, category
+    /// shrinkage and the dependence correction no longer apply.
+    pub learned_weights: bool,
+    /// Weight by accuracy over the last this many days (one of
+    /// [`ACCURACY_WINDOWS_DAYS`]) instead of all time. Not used when learned
+    /// weights replace the heuristic.
+    pub accuracy_window_days: Option<i32>,
+    /// Scale weights by each source's price discovery share, so venues that
+    /// move first count for more.
+    pub discovery_weighting: bool,
+    /// Shrink the combined weight of sources that copy each other. Not
+    /// applied to learned weights, which are fitted jointly; the explanation
+    /// then lists the overlap as uncorrected.
+    pub dependence_correction: bool,
+    /// Correct each source's probability with its fitted recalibration curve
+    /// before weighting and pooling.
+    pub recalibrate: bool,
+    /// Point in time source ages are measured from. `None` means now.
+    #[serde(skip)]
+    pub as_of: Option<DateTime<Utc>>,
+}
+
+impl Default for ConsensusConfig {
+    fn default() -> Self {
+        Self {
+            aggregation: AggregationMethod::default(),
+            min_resolved_for_accuracy: MIN_RESOLVED_FOR_ACCURACY,
+            outlier_threshold: OUTLIER_THRESHOLD,
+            outlier_mad_threshold: OUTLIER_MAD_THRESHOLD,
+            outlier_reference: OutlierReference::default(),
+            outlier_mode: OutlierMode::default(),
+            extremizing_exponent: None,
+            staleness_half_life_hours: STALENESS_HALF_LIFE_HOURS,
+            max_source_age_hours: MAX_SOURCE_AGE_HOURS,
+            interval_level: DEFAULT_INTERVAL_LEVEL,
+            liquidity_weighting: true,
+            min_liquidity_factor: MIN_LIQUIDITY_FACTOR,
+            play_money_liquidity_factor: MIN_LIQUIDITY_FACTOR,
+            learned_weights: false,
+            accuracy_window_days: None,
+            discovery_weighting: false,
+            dependence_correction: true,
+            recalibrate: true,
+            as_of: None,
+        }
+    }
+}
+
+impl ConsensusConfig {
+    /// Reject settings the engine cannot use meaningfully.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(days) = self.accuracy_window_days
+            && !ACCURACY_WINDOWS_DAYS.contains(&days)
+        {
+            anyhow::bail!("accuracy_window_days must be one of {:?}", ACCURACY_WINDOWS_DAYS);
+        }
+        if let AggregationMethod::TrimmedMean { trim_fraction } = self.aggregation
+            && !(0.0..0.5).contains(&trim_fraction)
+        {
+            anyhow::bail!("trim_fraction must be at least 0 and below 0.5");
+        }
+        if self.min_resolved_for_accuracy < 0 {
+            anyhow::bail!("min_resolved_for_accuracy must not be negative");
+        }
+        if !(0.0..).contains(&self.outlier_threshold) {
+            anyhow::bail!("outlier_threshold must not be negative");
+        }
+        if !positive(self.outlier_mad_threshold) {
+            anyhow::bail!("outlier_mad_threshold must be positive");
+        }
+        if !positive(self.interval_level) || self.interval_level >= 1.0 {
+            anyhow::bail!("interval_level must be between 0 and 1");
+        }
+        if !positive(self.staleness_half_life_hours) {
+            anyhow::bail!("staleness_half_life_hours must be positive");
+        }
+        if !positive(self.max_source_age_hours) {
+            anyhow::bail!("max_source_age_hours must be positive");
+        }
+        if !(0.0..=1.0).contains(&self.min_liquidity_factor) {
+            anyhow::bail!("min_liquidity_factor must be between 0 and 1");
+        }
+        if !(0.0..=1.0).contains(&self.play_money_liquidity_factor) {
+            anyhow::bail!("play_money_liquidity_factor must be between 0 and 1");
+        }
+        if let Some(exponent) = self.extremizing_exponent
+            && !positive(exponent)
+        {
+            anyhow::bail!("extremizing_exponent must be positive");
+        }
+        Ok(())
+    }
+}
 
-/// Outlier threshold (percentage points from consensus).
-const OUTLIER_THRESHOLD: f64 = 0.15;
+fn positive(value: f64) -> bool {
+    value.is_finite() && value > 0.0
+}
+
+/// Pseudo-count of resolved questions the overall record contributes when a
+/// category record is shrunk toward it. A category with this many resolved
+/// questions gets equal say with the overall figure.
+pub const CATEGORY_SHRINKAGE_PRIOR: f64 = 20.0;
+
+/// Choose the accuracy a source is weighted by in a given category.
+///
+/// The category record is shrunk toward the overall record in proportion to
+/// its sample size: `(n_cat * acc_cat + k * acc_all) / (n_cat + k)`, unless the
+/// overall record holds nothing beyond the category's. Without a category
+/// record the overall record is used as-is. Returns the accuracy, the
+/// resolved count backing it (the category's own count whenever a category
+/// record is used, so a thin category does not pass `min_resolved_for_accuracy`
+/// on the overall count), and the basis used.
+pub fn category_accuracy(
+    category: Option<AccuracyStats>,
+    overall: Option<AccuracyStats>,
+) -> (Option<f64>, i32, AccuracyBasis) {
+    match (category, overall) {
+        (Some(cat), Some(all)) if all.resolved_count > cat.resolved_count => {
+            let n = cat.resolved_count.max(0) as f64;
+            let shrunk = (n * cat.accuracy_pct + CATEGORY_SHRINKAGE_PRIOR * all.accuracy_pct)
+                / (n + CATEGORY_SHRINKAGE_PRIOR);
+            (Some(shrunk), cat.resolved_count, AccuracyBasis::CategoryShrunk)
+        }
+        (Some(cat), _) => (Some(cat.accuracy_pct), cat.resolved_count, AccuracyBasis::Category),
+        (None, Some(all)) => (Some(all.accuracy_pct), all.resolved_count, AccuracyBasis::Overall),
+        (None, None) => (None, 0, AccuracyBasis::None),
+    }
+}
 
-/// Calculate the Forecise Consensus from multiple source inputs.
+/// Calculate the Forecise Consensus from multiple source inputs with the
+/// default configuration.
 pub fn calculate_consensus(sources: &[SourceInput]) -> Result<ConsensusResult> {
+    calculate_consensus_with(sources, &ConsensusConfig::default())
+}
+
+/// Calculate the Forecise Consensus using the configured aggregation method.
+pub fn calculate_consensus_with(sources: &[SourceInput], config: &ConsensusConfig) -> Result<ConsensusResult> {
+    let aggregator = config.aggregation.aggregator();
+    calculate_consensus_with_aggregator(sources, config, aggregator.as_ref())
+}
+
+/// Calculate the Forecise Consensus with a caller-supplied aggregator.
+/// `config.aggregation` is ignored in favour of `aggregator`.
+pub fn calculate_consensus_with_aggregator(
+    sources: &[SourceInput],
+    config: &ConsensusConfig,
+    aggregator: &dyn Aggregator,
+) -> Result<ConsensusResult> {
     if sources.is_empty() {
         anyhow::bail!("No sources provided for consensus calculation");
     }
 
+    // Step 1: Leave out sources that have not been refreshed within the cutoff
+    // and correct the rest for their miscalibration
+    let now = config.as_of.unwrap_or_else(Utc::now);
+    let mut stale_sources = Vec::new();
+    let mut fresh = Vec::new();
+    let mut raw_probabilities = Vec::new();
+    for s in sources {
+        match age_hours(s, now) {
+            Some(age) if age > config.max_source_age_hours => stale_sources.push(StaleSource {
+                source_id: s.source_id.clone(),
+                source_name: s.source_name.clone(),
+                probability: s.probability,
+                age_hours: age,
+            }),
+            _ => {
+                let (source, raw) = recalibrated(s, config);
+                fresh.push(source);
+                raw_probabilities.push(raw);
+            }
+        }
+    }
+    if fresh.is_empty() {
+        anyhow::bail!(
+            "All {} sources are older than {}h",
+            sources.len(),
+            config.max_source_age_hours
+        );
+    }
+    let sources = &fresh[..];
+    let staleness: Vec<f64> = sources.iter()
+        .map(|s| staleness_factor(age_hours(s, now), config))
+        .collect();
+
     if sources.len() == 1 {
         let s = &sources[0];
+        let (interval_lower, interval_upper) =
+            interval::bootstrap_interval(&[s.probability], &[1.0], aggregator, config.interval_level);
         return Ok(ConsensusResult {
             probability: s.probability,
-            confidence: 0.3, // Low confidence with single source
+            confidence: 0.3 * staleness[0], // Low confidence with single source
             agreement: 1.0,
+            interval_lower,
+            interval_upper,
             source_count: 1,
             weights: vec![SourceWeight {
                 source_id: s.source_id.clone(),
                 source_name: s.source_name.clone(),
                 probability: s.probability,
+                raw_probability: raw_probabilities[0],
                 weight: 1.0,
                 accuracy_pct: s.accuracy_pct,
+                accuracy_basis: s.accuracy_basis,
+                resolved_count: s.resolved_count,
+                age_hours: age_hours(s, now),
+                staleness_factor: staleness[0],
+                liquidity: liquidity::liquidity_factor(s, config),
+                learned_weight: s.learned_weight.filter(|_| config.learned_weights),
+                outlier_factor: 1.0,
+                discovery_factor: 1.0,
+                dependence_factor: 1.0,
+                dependent_on: BTreeMap::new(),
             }],
             outliers: vec![],
+            method: aggregator.name().to_string(),
+            // A single source has nothing to combine, so nothing to extremize.
+            extremized_probability: s.probability,
+            extremizing_exponent: 1.0,
+            stale_sources,
         });
     }
 
-    // Step 1: Calculate weights based on accuracy
-    let weights = calculate_weights(sources);
+    // Step 2: Calculate weights based on accuracy, decayed by staleness,
+    // scaled by market depth and price discovery, and shrunk for sources that
+    // copy each other
+    let accuracy_weights = calculate_weights(sources, config);
+    let learned = uses_learned_weights(sources, config);
+    let decayed: Vec<f64> = accuracy_weights.iter()
+        .zip(staleness.iter())
+        .map(|(w, f)| w * f)
+        .collect();
+    // Accuracy weights sum to 1, so this is their weighted mean freshness.
+    let freshness: f64 = decayed.iter().sum();
+    let depth: Vec<LiquidityBreakdown> = sources.iter()
+        .map(|s| liquidity::liquidity_factor(s, config))
+        .collect();
+    let discovery: Vec<f64> = sources.iter().map(|s| discovery_factor(s, config)).collect();
+    let scaled: Vec<f64> = decayed.iter()
+        .zip(depth.iter())
+        .zip(discovery.iter())
+        .map(|((w, d), f)| w * d.factor * f)
+        .collect();
+    let ids: Vec<&str> = sources.iter().map(|s| s.source_id.as_str()).collect();
+    // Either side's estimate applies to the pair
+    let dependent_on: Vec<BTreeMap<String, f64>> = sources.iter()
+        .map(|s| {
+            sources.iter()
+                .filter(|o| o.source_id != s.source_id)
+                .filter_map(|o| {
+                    let r = s.dependence.get(&o.source_id).copied().unwrap_or(0.0)
+                        .max(o.dependence.get(&s.source_id).copied().unwrap_or(0.0))
+                        .min(1.0);
+                    (r > 0.0).then(|| (o.source_id.clone(), r))
+                })
+                .collect()
+        })
+        .collect();
+    let dependence_factors = if config.dependence_correction && !learned {
+        dependence::dependence_factors(&ids, &dependent_on.iter().collect::<Vec<_>>())
+    } else {
+        vec![1.0; sources.len()]
+    };
+    let scaled: Vec<f64> = scaled.iter().zip(&dependence_factors).map(|(w, f)| w * f).collect();
+    let weights = normalize(&scaled);
+
+    // Step 3: Check sources against a robust reference and apply the outlier mode
+    let probabilities: Vec<f64> = sources.iter().map(|s| s.probability).collect();
+    let checks = outlier::detect_outliers(&probabilities, &weights, aggregator, config);
+    let outlier_factors: Vec<f64> = checks.iter().map(|c| outlier::weight_factor(c, config)).collect();
+    let adjusted: Vec<f64> = weights.iter()
+        .zip(outlier_factors.iter())
+        .map(|(w, f)| w * f)
+        .collect();
+    let weights = normalize(&adjusted);
 
-    // Step 2: Compute weighted average
-    let consensus_prob: f64 = sources.iter()
-        .zip(weights.iter())
-        .map(|(s, w)| s.probability * w)
-        .sum();
+    // Step 4: Pool the weighted probabilities
+    let consensus_prob = aggregator.aggregate(&probabilities, &weights);
+    let extremizing_exponent = config.extremizing_exponent.unwrap_or(DEFAULT_EXTREMIZING_EXPONENT);
+    let extremized_prob = extremize::extremize(&probabilities, &weights, extremizing_exponent);
+    let (interval_lower, interval_upper) =
+        interval::bootstrap_interval(&probabilities, &weights, aggregator, config.interval_level);
 
-    // Step 3: Calculate agreement (inverse of variance)
+    // Step 5: Calculate agreement (inverse of variance)
     let variance: f64 = sources.iter()
         .zip(weights.iter())
         .map(|(s, w)| w * (s.probability - consensus_prob).powi(2))
         .sum();
     let agreement = (1.0 - variance.sqrt().min(1.0)).max(0.0);
 
-    // Step 4: Detect outliers
-    let mut outliers = Vec::new();
-    for source in sources {
-        let deviation = (source.probability - consensus_prob).abs();
-        if deviation > OUTLIER_THRESHOLD {
-            outliers.push(OutlierSource {
-                source_id: source.source_id.clone(),
-                source_name: source.source_name.clone(),
-                probability: source.probability,
-                deviation,
-            });
-        }
-    }
+    // Step 6: Record outliers
+    let outliers: Vec<OutlierSource> = sources.iter()
+        .zip(checks.iter())
+        .filter(|(_, c)| c.is_outlier)
+        .map(|(s, c)| OutlierSource {
+            source_id: s.source_id.clone(),
+            source_name: s.source_name.clone(),
+            probability: s.probability,
+            deviation: c.deviation,
+            reference: c.reference,
+            robust_z: c.robust_z,
+            action: config.outlier_mode,
+            reason: outlier::reason(c, config),
+        })
+        .collect();
 
-    // Step 5: Calculate confidence score
-    let confidence = calculate_confidence(sources, agreement);
+    // Step 7: Calculate confidence score, reduced when inputs are stale
+    let confidence = calculate_confidence(sources, agreement) * freshness;
 
-    // Step 6: Build weight details
+    // Step 8: Build weight details
     let weight_details: Vec<SourceWeight> = sources.iter()
         .zip(weights.iter())
-        .map(|(s, w)| SourceWeight {
+        .zip(staleness.iter())
+        .zip(depth)
+        .zip(outlier_factors)
+        .zip(discovery)
+        .zip(dependence_factors.into_iter().zip(dependent_on))
+        .zip(raw_probabilities)
+        .map(|(((((((s, w), f), d), o), p), (dep, dep_on)), raw)| SourceWeight {
             source_id: s.source_id.clone(),
             source_name: s.source_name.clone(),
             probability: s.probability,
+            raw_probability: raw,
             weight: *w,
             accuracy_pct: s.accuracy_pct,
+            accuracy_basis: s.accuracy_basis,
+            resolved_count: s.resolved_count,
+            age_hours: age_hours(s, now),
+            staleness_factor: *f,
+            liquidity: d,
+            learned_weight: if learned { s.learned_weight } else { None },
+            outlier_factor: o,
+            discovery_factor: p,
+            dependence_factor: dep,
+            dependent_on: dep_on,
         })
         .collect();
 
@@ -133,18 +589,79 @@ pub fn calculate_consensus(sources: &[SourceInput]) -> Result<ConsensusResult> {
         probability: consensus_prob.clamp(0.0, 1.0),
         confidence,
         agreement,
+        interval_lower,
+        interval_upper,
         source_count: sources.len(),
         weights: weight_details,
         outliers,
+        method: aggregator.name().to_string(),
+        extremized_probability: extremized_prob,
+        extremizing_exponent,
+        stale_sources,
     })
 }
 
+/// Hours between the source's last update and `now`.
+fn age_hours(source: &SourceInput, now: DateTime<Utc>) -> Option<f64> {
+    source.last_updated
+        .map(|t| ((now - t).num_seconds() as f64 / 3600.0).max(0.0))
+}
+
+/// The source with its probability corrected by its recalibration curve, and
+/// the quoted probability when the correction changed it.
+fn recalibrated(source: &SourceInput, config: &ConsensusConfig) -> (SourceInput, Option<f64>) {
+    let mut source = source.clone();
+    let Some(curve) = source.recalibration.as_ref().filter(|_| config.recalibrate) else {
+        return (source, None);
+    };
+    let raw = source.probability;
+    source.probability = curve.apply(raw);
+    let changed = (source.probability - raw).abs() > 1e-9;
+    (source, changed.then_some(raw))
+}
+
+/// Exponential time-decay: 1 for a fresh source, 0.5 at one half-life.
+fn staleness_factor(age_hours: Option<f64>, config: &ConsensusConfig) -> f64 {
+    match age_hours {
+        Some(age) if config.staleness_half_life_hours > 0.0 => {
+            0.5f64.powf(age / config.staleness_half_life_hours)
+        }
+        _ => 1.0,
+    }
+}
+
+/// `0.5 + share`: from 0.5 for a venue that always follows to 1.5 for one
+/// that always leads. 1 when discovery weighting is off or the share unknown.
+fn discovery_factor(source: &SourceInput, config: &ConsensusConfig) -> f64 {
+    match source.discovery_share {
+        Some(share) if config.discovery_weighting => 0.5 + share.clamp(0.0, 1.0),
+        _ => 1.0,
+    }
+}
+
+/// Scale weights to sum to 1, falling back to equal weights.
+fn normalize(weights: &[f64]) -> Vec<f64> {
+    let sum: f64 = weights.iter().sum();
+    if sum <= 0.0 {
+        vec![1.0 / weights.len() as f64; weights.len()]
+    } else {
+        weights.iter().map(|w| w / sum).collect()
+    }
+}
+
 /// Calculate normalized weights based on accuracy scores.
 /// Sources with more resolved questions and higher accuracy get higher weights.
-fn calculate_weights(sources: &[SourceInput]) -> Vec<f64> {
+/// When learned weights are enabled and available they are used instead, with
+/// sources missing from the learned set at the mean weight of 1.
+fn calculate_weights(sources: &[SourceInput], config: &ConsensusConfig) -> Vec<f64> {
+    if uses_learned_weights(sources, config) {
+        let learned: Vec<f64> = sources.iter().map(|s| s.learned_weight.unwrap_or(1.0)).collect();
+        return normalize(&learned);
+    }
+
     let raw_weights: Vec<f64> = sources.iter()
         .map(|s| {
-            if s.resolved_count >= MIN_RESOLVED_FOR_ACCURACY {
+            if s.resolved_count >= config.min_resolved_for_accuracy {
                 // Use accuracy as weight (default to 50% if unknown)
                 let accuracy = s.accuracy_pct.unwrap_or(50.0) / 100.0;
                 // Boost for more resolved questions (logarithmic)
@@ -158,12 +675,11 @@ fn calculate_weights(sources: &[SourceInput]) -> Vec<f64> {
         .collect();
 
     // Normalize weights to sum to 1
-    let sum: f64 = raw_weights.iter().sum();
-    if sum == 0.0 {
-        vec![1.0 / sources.len() as f64; sources.len()]
-    } else {
-        raw_weights.iter().map(|w| w / sum).collect()
-    }
+    normalize(&raw_weights)
+}
+
+fn uses_learned_weights(sources: &[SourceInput], config: &ConsensusConfig) -> bool {
+    config.learned_weights && sources.iter().any(|s| s.learned_weight.is_some())
 }
 
 /// Calculate confidence score (0-1) based on:
@@ -222,6 +738,8 @@ mod tests {
                 accuracy_pct: Some(89.2),
                 resolved_count: 134,
                 volume: Some(5_000_000.0),
+                accuracy_basis: AccuracyBasis::Overall,
+                ..Default::default()
             },
             SourceInput {
                 source_id: "kalshi".into(),
@@ -230,6 +748,8 @@ mod tests {
                 accuracy_pct: Some(81.3),
                 resolved_count: 67,
                 volume: Some(2_000_000.0),
+                accuracy_basis: AccuracyBasis::Overall,
+                ..Default::default()
             },
             SourceInput {
                 source_id: "metaculus".into(),
@@ -238,6 +758,9 @@ mod tests {
                 accuracy_pct: Some(84.7),
                 resolved_count: 89,
                 volume: None,
+                accuracy_basis: AccuracyBasis::Overall,
+                forecaster_count: Some(600),
+                ..Default::default()
             },
         ]
     }
@@ -252,6 +775,7 @@ mod tests {
         assert!(result.confidence > 0.0 && result.confidence <= 1.0);
         assert_eq!(result.source_count, 3);
         assert!(result.agreement > 0.5); // Sources are reasonably close
+        assert!(result.interval_lower < result.probability && result.probability < result.interval_upper);
     }
 
     #[test]
@@ -263,6 +787,8 @@ mod tests {
             accuracy_pct: Some(85.0),
             resolved_count: 100,
             volume: Some(1_000_000.0),
+            accuracy_basis: AccuracyBasis::Overall,
+            ..Default::default()
         }];
         let result = calculate_consensus(&sources).unwrap();
         assert!((result.probability - 0.65).abs() < 1e-10);
@@ -279,6 +805,8 @@ mod tests {
                 accuracy_pct: Some(90.0),
                 resolved_count: 100,
                 volume: Some(5_000_000.0),
+                accuracy_basis: AccuracyBasis::Overall,
+                ..Default::default()
             },
             SourceInput {
                 source_id: "b".into(),
@@ -287,6 +815,8 @@ mod tests {
                 accuracy_pct: Some(85.0),
                 resolved_count: 80,
                 volume: Some(3_000_000.0),
+                accuracy_basis: AccuracyBasis::Overall,
+                ..Default::default()
             },
             SourceInput {
                 source_id: "c".into(),
@@ -295,21 +825,257 @@ mod tests {
                 accuracy_pct: Some(64.0),
                 resolved_count: 48,
                 volume: Some(500_000.0),
+                accuracy_basis: AccuracyBasis::Overall,
+                ..Default::default()
             },
         ];
 
         let result = calculate_consensus(&sources).unwrap();
-        assert!(!result.outliers.is_empty(), "Should detect outlier source C");
+        assert_eq!(result.outliers.len(), 1, "Should detect outlier source C only");
+        assert_eq!(result.outliers[0].source_id, "c");
+        assert!(!result.outliers[0].reason.is_empty());
+
+        // Excluding the outlier takes it out of the headline number
+        let config = ConsensusConfig { outlier_mode: OutlierMode::Exclude, ..ConsensusConfig::default() };
+        let excluded = calculate_consensus_with(&sources, &config).unwrap();
+        assert!(excluded.probability > result.probability);
+        assert_eq!(excluded.weights[2].weight, 0.0);
+        assert_eq!(excluded.outliers[0].action, OutlierMode::Exclude);
     }
 
     #[test]
     fn test_weights_normalize() {
         let sources = test_sources();
-        let weights = calculate_weights(&sources);
+        let weights = calculate_weights(&sources, &ConsensusConfig::default());
         let sum: f64 = weights.iter().sum();
         assert!((sum - 1.0).abs() < 1e-10, "Weights should sum to 1.0");
     }
 
+    #[test]
+    fn test_configured_aggregation_method() {
+        let sources = test_sources();
+        let config: ConsensusConfig =
+            serde_json::from_str(r#"{"aggregation": {"name": "weighted_median"}}"#).unwrap();
+        assert_eq!(config.outlier_threshold, OUTLIER_THRESHOLD);
+
+        let result = calculate_consensus_with(&sources, &config).unwrap();
+        assert_eq!(result.method, "weighted_median");
+        // Polymarket carries the middle of the weight mass
+        assert!((result.probability - 0.67).abs() < 1e-10);
+
+        let linear = calculate_consensus(&sources).unwrap();
+        assert_eq!(linear.method, "linear_pool");
+    }
+
+    #[test]
+    fn test_extremized_consensus() {
+        let sources = test_sources();
+        let result = calculate_consensus(&sources).unwrap();
+        assert_eq!(result.extremizing_exponent, DEFAULT_EXTREMIZING_EXPONENT);
+        // All sources lean YES, so extremizing moves further from 50%
+        assert!(result.extremized_probability > result.probability);
+
+        let config = ConsensusConfig {
+            extremizing_exponent: Some(1.0),
+            ..ConsensusConfig::default()
+        };
+        let plain = calculate_consensus_with(&sources, &config).unwrap();
+        assert!(plain.extremized_probability < result.extremized_probability);
+    }
+
+    #[test]
+    fn test_stale_sources_decay_and_drop_out() {
+        let now = Utc::now();
+        let mut sources = test_sources();
+        sources[0].last_updated = Some(now - chrono::Duration::minutes(5));
+        sources[1].last_updated = Some(now - chrono::Duration::hours(24));
+        sources[2].last_updated = Some(now - chrono::Duration::hours(100));
+
+        let config = ConsensusConfig { as_of: Some(now), ..ConsensusConfig::default() };
+        let result = calculate_consensus_with(&sources, &config).unwrap();
+
+        assert_eq!(result.source_count, 2);
+        assert_eq!(result.stale_sources.len(), 1);
+        assert_eq!(result.stale_sources[0].source_id, "metaculus");
+
+        let kalshi = result.weights.iter().find(|w| w.source_id == "kalshi").unwrap();
+        assert!((kalshi.staleness_factor - 0.5).abs() < 1e-10);
+
+        // Same two sources, both fresh: confidence is higher
+        let mut fresh = sources[..2].to_vec();
+        fresh[1].last_updated = fresh[0].last_updated;
+        let fresh_result = calculate_consensus_with(&fresh, &config).unwrap();
+        assert!(fresh_result.confidence > result.confidence);
+    }
+
+    #[test]
+    fn test_all_sources_stale() {
+        let now = Utc::now();
+        let mut sources = test_sources();
+        for s in &mut sources {
+            s.last_updated = Some(now - chrono::Duration::days(30));
+        }
+        let config = ConsensusConfig { as_of: Some(now), ..ConsensusConfig::default() };
+        assert!(calculate_consensus_with(&sources, &config).is_err());
+    }
+
+    #[test]
+    fn test_thin_markets_lose_weight() {
+        let mut sources = test_sources();
+        let deep = calculate_consensus(&sources).unwrap();
+
+        // Kalshi trading on a thin, wide book
+        sources[1].volume = Some(800.0);
+        sources[1].liquidity = Some(200.0);
+        sources[1].spread = Some(0.08);
+        let thin = calculate_consensus(&sources).unwrap();
+
+        assert!(thin.weights[1].weight < deep.weights[1].weight);
+        assert!(thin.weights[1].liquidity.factor < 0.2);
+        let total: f64 = thin.weights.iter().map(|w| w.weight).sum();
+        assert!((total - 1.0).abs() < 0.001);
+
+        let config = ConsensusConfig { liquidity_weighting: false, ..ConsensusConfig::default() };
+        let off = calculate_consensus_with(&sources, &config).unwrap();
+        assert!((off.weights[1].weight - deep.weights[1].weight).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_learned_weights_replace_heuristic() {
+        let mut sources = test_sources();
+        let heuristic = calculate_consensus(&sources).unwrap();
+
+        sources[0].learned_weight = Some(0.5);
+        sources[1].learned_weight = Some(2.0);
+        // Available weights are ignored unless enabled
+        let default = calculate_consensus(&sources).unwrap();
+        assert!((default.weights[1].weight - heuristic.weights[1].weight).abs() < 1e-10);
+        assert_eq!(default.weights[1].learned_weight, None);
+
+        let config = ConsensusConfig { learned_weights: true, ..ConsensusConfig::default() };
+        let learned = calculate_consensus_with(&sources, &config).unwrap();
+        // Kalshi 2.0, Polymarket 0.5, Metaculus (not in the set) 1.0
+        assert!((learned.weights[1].weight - 2.0 / 3.5).abs() < 1e-10);
+        assert!((learned.weights[2].weight - 1.0 / 3.5).abs() < 1e-10);
+        assert_eq!(learned.weights[1].learned_weight, Some(2.0));
+    }
+
+    #[test]
+    fn test_discovery_weighting_favours_leaders() {
+        let mut sources = test_sources();
+        sources[0].discovery_share = Some(0.9);
+        sources[1].discovery_share = Some(0.1);
+
+        // Off by default
+        let off = calculate_consensus(&sources).unwrap();
+        assert!(off.weights.iter().all(|w| w.discovery_factor == 1.0));
+
+        let config = ConsensusConfig { discovery_weighting: true, ..ConsensusConfig::default() };
+        let on = calculate_consensus_with(&sources, &config).unwrap();
+        assert!((on.weights[0].discovery_factor - 1.4).abs() < 1e-10);
+        assert!((on.weights[1].discovery_factor - 0.6).abs() < 1e-10);
+        assert_eq!(on.weights[2].discovery_factor, 1.0);
+        assert!(on.weights[0].weight > off.weights[0].weight);
+        assert!(on.weights[1].weight < off.weights[1].weight);
+    }
+
+    #[test]
+    fn test_dependent_sources_share_weight() {
+        let mut sources = test_sources();
+        let independent = calculate_consensus(&sources).unwrap();
+
+        // Metaculus just tracks Polymarket
+        sources[2].dependence.insert("polymarket".into(), 0.9);
+        let corrected = calculate_consensus(&sources).unwrap();
+        assert!((corrected.weights[0].dependence_factor - 1.0 / 1.9).abs() < 1e-10);
+        assert!((corrected.weights[2].dependence_factor - 1.0 / 1.9).abs() < 1e-10);
+        assert_eq!(corrected.weights[1].dependence_factor, 1.0);
+        assert!(corrected.weights[1].weight > independent.weights[1].weight);
+        assert_eq!(corrected.weights[2].dependent_on.get("polymarket"), Some(&0.9));
+
+        let config = ConsensusConfig { dependence_correction: false, ..ConsensusConfig::default() };
+        let off = calculate_consensus_with(&sources, &config).unwrap();
+        assert!((off.weights[1].weight - independent.weights[1].weight).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_recalibration_corrects_before_pooling() {
+        let mut sources = test_sources();
+        let raw = calculate_consensus(&sources).unwrap();
+
+        // Kalshi overprices YES by about a logit unit
+        sources[1].recalibration = Some(Recalibration::Platt { slope: 1.0, intercept: -1.0 });
+        let corrected = calculate_consensus(&sources).unwrap();
+        let kalshi = &corrected.weights[1];
+        assert_eq!(kalshi.raw_probability, Some(sources[1].probability));
+        assert!(kalshi.probability < sources[1].probability);
+        assert_eq!(corrected.weights[0].raw_probability, None);
+        assert!(corrected.probability < raw.probability);
+
+        let config = ConsensusConfig { recalibrate: false, ..ConsensusConfig::default() };
+        let off = calculate_consensus_with(&sources, &config).unwrap();
+        assert!((off.probability - raw.probability).abs() < 1e-10);
+        assert_eq!(off.weights[1].raw_probability, None);
+    }
+
+    #[test]
+    fn test_category_accuracy_shrinks_small_samples() {
+        let overall = Some(AccuracyStats { accuracy_pct: 80.0, resolved_count: 200 });
+
+        // 5 resolved in category: stays close to the overall figure
+        let small = Some(AccuracyStats { accuracy_pct: 100.0, resolved_count: 5 });
+        let (acc, resolved, basis) = category_accuracy(small, overall);
+        assert_eq!(basis, AccuracyBasis::CategoryShrunk);
+        // The category's own count, too few for accuracy weighting
+        assert_eq!(resolved, 5);
+        assert!(resolved < MIN_RESOLVED_FOR_ACCURACY);
+        assert!((acc.unwrap() - 84.0).abs() < 1e-10);
+
+        // 180 resolved in category: dominated by the category figure
+        let large = Some(AccuracyStats { accuracy_pct: 90.0, resolved_count: 180 });
+        let (acc, _, _) = category_accuracy(large, overall);
+        assert!((acc.unwrap() - 89.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(ConsensusConfig::default().validate().is_ok());
+        let invalid = [
+            ConsensusConfig { outlier_threshold: -0.1, ..ConsensusConfig::default() },
+            ConsensusConfig { min_resolved_for_accuracy: -1, ..ConsensusConfig::default() },
+            ConsensusConfig { interval_level: 1.0, ..ConsensusConfig::default() },
+            ConsensusConfig { staleness_half_life_hours: 0.0, ..ConsensusConfig::default() },
+            ConsensusConfig { accuracy_window_days: Some(30), ..ConsensusConfig::default() },
+            ConsensusConfig { interval_level: f64::NAN, ..ConsensusConfig::default() },
+            ConsensusConfig {
+                aggregation: AggregationMethod::TrimmedMean { trim_fraction: 0.5 },
+                ..ConsensusConfig::default()
+            },
+            ConsensusConfig {
+                aggregation: AggregationMethod::TrimmedMean { trim_fraction: -0.1 },
+                ..ConsensusConfig::default()
+            },
+        ];
+        let trimmed = ConsensusConfig {
+            aggregation: AggregationMethod::TrimmedMean { trim_fraction: 0.0 },
+            ..ConsensusConfig::default()
+        };
+        assert!(trimmed.validate().is_ok());
+        for config in invalid {
+            assert!(config.validate().is_err(), "{:?}", config);
+        }
+    }
+
+    #[test]
+    fn test_category_accuracy_fallbacks() {
+        let stats = Some(AccuracyStats { accuracy_pct: 75.0, resolved_count: 40 });
+        assert_eq!(category_accuracy(None, stats), (Some(75.0), 40, AccuracyBasis::Overall));
+        assert_eq!(category_accuracy(stats, None), (Some(75.0), 40, AccuracyBasis::Category));
+        // Every resolved question is in this category: nothing to shrink toward
+        assert_eq!(category_accuracy(stats, stats), (Some(75.0), 40, AccuracyBasis::Category));
+        assert_eq!(category_accuracy(None, None), (None, 0, AccuracyBasis::None));
+    }
+
     #[test]
     fn test_empty_sources() {
         let result = calculate_consensus(&[]);
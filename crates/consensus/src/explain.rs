@@ -0,0 +1,259 @@
+//! Per-source breakdown of a consensus.
+//!
+//! Works from the stored weight details of a snapshot: each source's final
+//! weight, what it was built from, its share of the consensus, and how far the
+//! consensus would move if the source were left out (the other weights
+//! renormalized, same aggregation).
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::aggregator::Aggregator;
+use crate::engine::{AccuracyBasis, SourceWeight};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceExplanation {
+    pub source_id: String,
+    pub source_name: String,
+    pub probability: f64,
+    /// Probability as quoted, when recalibration changed it.
+    pub raw_probability: Option<f64>,
+    /// Normalized weight in the consensus.
+    pub weight: f64,
+    pub accuracy_pct: Option<f64>,
+    pub accuracy_basis: AccuracyBasis,
+    pub resolved_count: i32,
+    pub learned_weight: Option<f64>,
+    pub age_hours: Option<f64>,
+    pub staleness_factor: f64,
+    pub liquidity_factor: f64,
+    pub outlier_factor: f64,
+    pub discovery_factor: f64,
+    pub dependence_factor: f64,
+    /// Dependence on other sources in the consensus, by source id.
+    pub dependent_on: BTreeMap<String, f64>,
+    /// Why the source got its weight, in words.
+    pub reason: String,
+    /// `weight * probability` in percentage points. Sums to the consensus
+    /// under the linear pool; an approximation for other methods.
+    pub contribution_points: f64,
+    /// Source minus consensus, in percentage points.
+    pub difference_points: f64,
+    /// Consensus minus the consensus without this source, in percentage
+    /// points. `None` for a lone source.
+    pub shift_without_points: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Explanation {
+    pub probability: f64,
+    pub method: String,
+    /// Sources by weight, heaviest first.
+    pub sources: Vec<SourceExplanation>,
+}
+
+/// Explain a consensus from its weight details.
+pub fn explain(weights: &[SourceWeight], aggregator: &dyn Aggregator) -> Explanation {
+    let probabilities: Vec<f64> = weights.iter().map(|w| w.probability).collect();
+    let normalized = normalize(&weights.iter().map(|w| w.weight).collect::<Vec<_>>());
+    let probability = if weights.is_empty() {
+        0.5
+    } else {
+        aggregator.aggregate(&probabilities, &normalized)
+    };
+
+    let mut sources: Vec<SourceExplanation> = weights.iter()
+        .enumerate()
+        .map(|(i, w)| SourceExplanation {
+            source_id: w.source_id.clone(),
+            source_name: w.source_name.clone(),
+            probability: w.probability,
+            raw_probability: w.raw_probability,
+            weight: normalized[i],
+            accuracy_pct: w.accuracy_pct,
+            accuracy_basis: w.accuracy_basis,
+            resolved_count: w.resolved_count,
+            learned_weight: w.learned_weight,
+            age_hours: w.age_hours,
+            staleness_factor: w.staleness_factor,
+            liquidity_factor: w.liquidity.factor,
+            outlier_factor: w.outlier_factor,
+            discovery_factor: w.discovery_factor,
+            dependence_factor: w.dependence_factor,
+            dependent_on: w.dependent_on.clone(),
+            reason: reason(w, weights),
+            contribution_points: normalized[i] * w.probability * 100.0,
+            difference_points: (w.probability - probability) * 100.0,
+            shift_without_points: without(&probabilities, &normalized, i, aggregator)
+                .map(|p| (probability - p) * 100.0),
+        })
+        .collect();
+    sources.sort_by(|a, b| b.weight.partial_cmp(&a.weight).unwrap_or(std::cmp::Ordering::Equal));
+
+    Explanation {
+        probability,
+        method: aggregator.name().to_string(),
+        sources,
+    }
+}
+
+fn reason(w: &SourceWeight, all: &[SourceWeight]) -> String {
+    let mut parts = Vec::new();
+    match (w.learned_weight, w.accuracy_pct) {
+        (Some(learned), _) => parts.push(format!("learned weight {:.2}", learned)),
+        (None, Some(accuracy)) => {
+            let basis = match w.accuracy_basis {
+                AccuracyBasis::Category => "in this category",
+                AccuracyBasis::CategoryShrunk => "in this category, shrunk toward overall",
+                AccuracyBasis::Overall => "overall",
+                AccuracyBasis::None => "",
+            };
+            parts.push(format!("{:.1}% accuracy on {} resolved {}", accuracy, w.resolved_count, basis).trim_end().to_string());
+        }
+        (None, None) => parts.push("no accuracy record, default weight".to_string()),
+    }
+    if let Some(raw) = w.raw_probability {
+        parts.push(format!("recalibrated {:.1}% → {:.1}%", raw * 100.0, w.probability * 100.0));
+    }
+    if w.liquidity.factor < 1.0 {
+        parts.push(format!("thin market ×{:.2}", w.liquidity.factor));
+    }
+    if w.staleness_factor < 1.0 {
+        match w.age_hours {
+            Some(age) => parts.push(format!("stale ×{:.2} ({:.0}h old)", w.staleness_factor, age)),
+            None => parts.push(format!("stale ×{:.2}", w.staleness_factor)),
+        }
+    }
+    if w.discovery_factor > 1.0 {
+        parts.push(format!("leads price discovery ×{:.2}", w.discovery_factor));
+    } else if w.discovery_factor < 1.0 {
+        parts.push(format!("follows other venues ×{:.2}", w.discovery_factor));
+    }
+    let overlaps = || -> String {
+        w.dependent_on.iter()
+            .map(|(id, r)| {
+                let name = all.iter().find(|o| &o.source_id == id).map_or(id.as_str(), |o| o.source_name.as_str());
+                format!("{} {:.2}", name, r)
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    if w.dependence_factor < 1.0 {
+        parts.push(format!("overlaps {} ×{:.2}", overlaps(), w.dependence_factor));
+    } else if w.learned_weight.is_some() && !w.dependent_on.is_empty() {
+        // Learned weights are fitted jointly, so the correction is skipped
+        parts.push(format!("overlaps {}, not corrected under learned weights", overlaps()));
+    }
+    if w.outlier_factor < 1.0 {
+        parts.push(format!("outlier ×{:.2}", w.outlier_factor));
+    }
+    parts.join("; ")
+}
+
+fn normalize(weights: &[f64]) -> Vec<f64> {
+    let total: f64 = weights.iter().sum();
+    if total > 0.0 {
+        weights.iter().map(|w| w / total).collect()
+    } else {
+        vec![1.0 / weights.len().max(1) as f64; weights.len()]
+    }
+}
+
+fn without(probabilities: &[f64], weights: &[f64], skip: usize, aggregator: &dyn Aggregator) -> Option<f64> {
+    if probabilities.len() < 2 {
+        return None;
+    }
+    let (ps, ws): (Vec<f64>, Vec<f64>) = probabilities.iter()
+        .zip(weights)
+        .enumerate()
+        .filter(|(j, _)| *j != skip)
+        .map(|(_, (p, w))| (*p, *w))
+        .unzip();
+    Some(aggregator.aggregate(&ps, &normalize(&ws)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aggregator::LinearPool;
+    use crate::engine::{ConsensusConfig, SourceInput, calculate_consensus, calculate_consensus_with};
+    use crate::recalibration::Recalibration;
+
+    fn source(id: &str, probability: f64, accuracy_pct: f64) -> SourceInput {
+        SourceInput {
+            source_id: id.into(),
+            source_name: id.into(),
+            probability,
+            accuracy_pct: Some(accuracy_pct),
+            resolved_count: 100,
+            accuracy_basis: AccuracyBasis::Overall,
+            ..SourceInput::default()
+        }
+    }
+
+    #[test]
+    fn test_linear_pool_breakdown() {
+        let result = calculate_consensus(&[
+            source("polymarket", 0.70, 85.0),
+            source("metaculus", 0.60, 80.0),
+            source("manifold", 0.50, 75.0),
+        ]).unwrap();
+        let explanation = explain(&result.weights, &LinearPool);
+
+        assert!((explanation.probability - result.probability).abs() < 1e-10);
+        let total: f64 = explanation.sources.iter().map(|s| s.contribution_points).sum();
+        assert!((total - result.probability * 100.0).abs() < 1e-8);
+        // Heaviest first; dropping the highest source moves the consensus down
+        assert_eq!(explanation.sources[0].source_id, "polymarket");
+        assert!(explanation.sources[0].shift_without_points.unwrap() > 0.0);
+        assert!(explanation.sources[2].shift_without_points.unwrap() < 0.0);
+        assert!(explanation.sources[0].reason.contains("85.0% accuracy on 100 resolved overall"));
+    }
+
+    #[test]
+    fn test_dependence_is_explained() {
+        let mut metaculus = source("metaculus", 0.60, 80.0);
+        metaculus.dependence.insert("polymarket".into(), 0.8);
+        let result = calculate_consensus(&[source("polymarket", 0.70, 85.0), metaculus]).unwrap();
+        let explanation = explain(&result.weights, &LinearPool);
+        let m = explanation.sources.iter().find(|s| s.source_id == "metaculus").unwrap();
+        assert!((m.dependence_factor - 1.0 / 1.8).abs() < 1e-10);
+        assert!(m.reason.contains("overlaps polymarket 0.80 ×0.56"));
+        // Recorded on one side, applied to both
+        let p = explanation.sources.iter().find(|s| s.source_id == "polymarket").unwrap();
+        assert!(p.reason.contains("overlaps metaculus 0.80 ×0.56"));
+    }
+
+    #[test]
+    fn test_uncorrected_dependence_is_explained() {
+        let mut metaculus = source("metaculus", 0.60, 80.0);
+        metaculus.dependence.insert("polymarket".into(), 0.8);
+        metaculus.learned_weight = Some(1.2);
+        let config = ConsensusConfig { learned_weights: true, ..ConsensusConfig::default() };
+        let result = calculate_consensus_with(&[source("polymarket", 0.70, 85.0), metaculus], &config).unwrap();
+        let explanation = explain(&result.weights, &LinearPool);
+        let m = explanation.sources.iter().find(|s| s.source_id == "metaculus").unwrap();
+        assert_eq!(m.dependence_factor, 1.0);
+        assert!(m.reason.contains("overlaps polymarket 0.80, not corrected under learned weights"), "{}", m.reason);
+    }
+
+    #[test]
+    fn test_recalibration_is_explained() {
+        let mut manifold = source("manifold", 0.10, 75.0);
+        manifold.recalibration = Some(Recalibration::Platt { slope: 1.0, intercept: -0.5 });
+        let result = calculate_consensus(&[source("polymarket", 0.20, 85.0), manifold]).unwrap();
+        let explanation = explain(&result.weights, &LinearPool);
+        let m = explanation.sources.iter().find(|s| s.source_id == "manifold").unwrap();
+        assert_eq!(m.raw_probability, Some(0.10));
+        assert!(m.probability < 0.10);
+        assert!(m.reason.contains("recalibrated 10.0% → 6.3%"), "{}", m.reason);
+    }
+
+    #[test]
+    fn test_single_source_has_no_shift() {
+        let result = calculate_consensus(&[source("polymarket", 0.7, 85.0)]).unwrap();
+        let explanation = explain(&result.weights, &LinearPool);
+        assert!(explanation.sources[0].shift_without_points.is_none());
+        assert!(explanation.sources[0].difference_points.abs() < 1e-10);
+    }
+}
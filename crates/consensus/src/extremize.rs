@@ -0,0 +1,130 @@
+//! Extremized log-odds pooling.
+//!
+//! Averaging calibrated forecasters yields an under-confident aggregate, since
+//! each forecaster only holds part of the available information. Extremizing
+//! pools in log-odds space and scales the result by an exponent `a > 1`:
+//! `p = sigmoid(a * Σ w_i * logit(p_i))`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::aggregator::{Aggregator, LogOddsPool, logit, sigmoid};
+
+/// Exponent used until one has been fitted from resolved history.
+pub const DEFAULT_EXTREMIZING_EXPONENT: f64 = 1.5;
+
+/// The exponent is fitted on the consensus this long before resolution, when
+/// prices have not yet converged on the outcome.
+pub const EXTREMIZING_FIT_HORIZON_HOURS: i64 = 24;
+
+/// Minimum resolved markets before a fitted exponent is trusted.
+pub const MIN_SAMPLES_FOR_FIT: usize = 50;
+
+/// Fitted exponents are kept within this range.
+const EXPONENT_BOUNDS: (f64, f64) = (0.5, 5.0);
+
+/// Pool in log-odds space and apply the extremizing exponent.
+pub fn extremize(probabilities: &[f64], weights: &[f64], exponent: f64) -> f64 {
+    let pooled = LogOddsPool.aggregate(probabilities, weights);
+    sigmoid(exponent * logit(pooled))
+}
+
+/// Result of fitting the extremizing exponent on resolved markets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtremizingFit {
+    pub exponent: f64,
+    pub sample_size: usize,
+    /// Mean log loss of the un-extremized log-odds pool (exponent 1).
+    pub log_loss_raw: f64,
+    /// Mean log loss at the fitted exponent.
+    pub log_loss_extremized: f64,
+}
+
+/// Fit the exponent that minimizes mean log loss over resolved markets.
+///
+/// Each sample is the un-extremized log-odds pool at (or shortly before)
+/// resolution and the outcome (0 or 1). This is a one-parameter logistic
+/// regression without intercept, which is convex, so Newton's method
+/// converges from `a = 1`. Returns `None` below [`MIN_SAMPLES_FOR_FIT`].
+pub fn fit_extremizing_exponent(samples: &[(f64, f64)]) -> Option<ExtremizingFit> {
+    if samples.len() < MIN_SAMPLES_FOR_FIT {
+        return None;
+    }
+
+    let xs: Vec<(f64, f64)> = samples.iter().map(|(p, y)| (logit(*p), *y)).collect();
+
+    let mut a: f64 = 1.0;
+    for _ in 0..50 {
+        let (mut gradient, mut hessian) = (0.0, 0.0);
+        for (x, y) in &xs {
+            let q = sigmoid(a * x);
+            gradient += (q - y) * x;
+            hessian += q * (1.0 - q) * x * x;
+        }
+        if hessian <= 1e-12 {
+            break;
+        }
+        let step = gradient / hessian;
+        a = (a - step).clamp(EXPONENT_BOUNDS.0, EXPONENT_BOUNDS.1);
+        if step.abs() < 1e-8 {
+            break;
+        }
+    }
+
+    Some(ExtremizingFit {
+        exponent: a,
+        sample_size: samples.len(),
+        log_loss_raw: mean_log_loss(&xs, 1.0),
+        log_loss_extremized: mean_log_loss(&xs, a),
+    })
+}
+
+fn mean_log_loss(xs: &[(f64, f64)], a: f64) -> f64 {
+    let total: f64 = xs.iter()
+        .map(|(x, y)| {
+            let q = sigmoid(a * x).clamp(1e-12, 1.0 - 1e-12);
+            -(y * q.ln() + (1.0 - y) * (1.0 - q).ln())
+        })
+        .sum();
+    total / xs.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extremize_pushes_away_from_half() {
+        let p = extremize(&[0.7, 0.7], &[0.5, 0.5], 2.0);
+        // logit(0.7) * 2 -> odds (7/3)^2
+        let expected = 49.0 / (49.0 + 9.0);
+        assert!((p - expected).abs() < 1e-10);
+
+        let p = extremize(&[0.3, 0.3], &[0.5, 0.5], 2.0);
+        assert!((p - (1.0 - expected)).abs() < 1e-10);
+
+        // Exponent 1 is the plain log-odds pool
+        let p = extremize(&[0.7, 0.6], &[0.5, 0.5], 1.0);
+        assert!((p - LogOddsPool.aggregate(&[0.7, 0.6], &[0.5, 0.5])).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_fit_recovers_underconfidence() {
+        // Forecasts of 0.7 on events that happen 90% of the time are
+        // under-confident: the fitted exponent should be logit(0.9)/logit(0.7).
+        let mut samples = Vec::new();
+        for i in 0..100 {
+            let outcome = if i % 10 == 0 { 0.0 } else { 1.0 };
+            samples.push((0.7, outcome));
+            samples.push((0.3, 1.0 - outcome));
+        }
+        let fit = fit_extremizing_exponent(&samples).unwrap();
+        let expected = logit(0.9) / logit(0.7);
+        assert!((fit.exponent - expected).abs() < 1e-4);
+        assert!(fit.log_loss_extremized < fit.log_loss_raw);
+    }
+
+    #[test]
+    fn test_fit_needs_enough_samples() {
+        assert!(fit_extremizing_exponent(&[(0.7, 1.0); 10]).is_none());
+    }
+}
@@ -0,0 +1,143 @@
+//! Uncertainty intervals for the consensus probability.
+//!
+//! The interval comes from a bootstrap over the weighted source inputs: each
+//! resample draws sources with replacement, keeps their weights, perturbs each
+//! probability by a small amount of log-odds noise (a single market price is
+//! itself a noisy estimate), and re-runs the aggregator. Tight agreement among
+//! many sources gives a narrow interval; a few disagreeing sources a wide one.
+
+use crate::aggregator::{Aggregator, logit, sigmoid};
+
+/// Bootstrap resamples per interval.
+pub const BOOTSTRAP_RESAMPLES: usize = 1000;
+
+/// Default coverage of the interval.
+pub const DEFAULT_INTERVAL_LEVEL: f64 = 0.9;
+
+/// Standard deviation of the log-odds noise added to each source probability.
+/// At 50% this is roughly ±4 percentage points.
+const SOURCE_NOISE_LOGIT: f64 = 0.15;
+
+/// Bootstrap a `level` interval for `aggregator` over the weighted sources.
+///
+/// Resampling is seeded from the inputs, so the same inputs always give the
+/// same interval. Returns `(lower, upper)`.
+pub fn bootstrap_interval(
+    probabilities: &[f64],
+    weights: &[f64],
+    aggregator: &dyn Aggregator,
+    level: f64,
+) -> (f64, f64) {
+    let n = probabilities.len();
+    if n == 0 {
+        return (0.0, 1.0);
+    }
+
+    let mut rng = SplitMix64::seeded(probabilities, weights);
+    let mut estimates = Vec::with_capacity(BOOTSTRAP_RESAMPLES);
+    let mut probs = vec![0.0; n];
+    let mut ws = vec![0.0; n];
+
+    for _ in 0..BOOTSTRAP_RESAMPLES {
+        for i in 0..n {
+            let j = rng.next_index(n);
+            probs[i] = sigmoid(logit(probabilities[j]) + SOURCE_NOISE_LOGIT * rng.next_normal());
+            ws[i] = weights[j];
+        }
+        let total: f64 = ws.iter().sum();
+        if total > 0.0 {
+            ws.iter_mut().for_each(|w| *w /= total);
+        } else {
+            ws.iter_mut().for_each(|w| *w = 1.0 / n as f64);
+        }
+        estimates.push(aggregator.aggregate(&probs, &ws));
+    }
+
+    estimates.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let tail = (1.0 - level.clamp(0.0, 1.0)) / 2.0;
+    (quantile(&estimates, tail), quantile(&estimates, 1.0 - tail))
+}
+
+/// Linear-interpolated quantile of sorted values.
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let pos = q * (sorted.len() - 1) as f64;
+    let (lo, hi) = (pos.floor() as usize, pos.ceil() as usize);
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (pos - lo as f64)
+}
+
+/// Small deterministic PRNG, enough for resampling.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn seeded(probabilities: &[f64], weights: &[f64]) -> Self {
+        let seed = probabilities.iter()
+            .chain(weights)
+            .fold(0x9E37_79B9_7F4A_7C15u64, |acc, x| {
+                acc.rotate_left(5) ^ x.to_bits()
+            });
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1).
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn next_index(&mut self, n: usize) -> usize {
+        ((self.next_f64() * n as f64) as usize).min(n - 1)
+    }
+
+    /// Standard normal via Box-Muller.
+    fn next_normal(&mut self) -> f64 {
+        let u1 = self.next_f64().max(f64::MIN_POSITIVE);
+        let u2 = self.next_f64();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aggregator::LinearPool;
+
+    #[test]
+    fn test_interval_contains_estimate() {
+        let probs = [0.60, 0.62, 0.65, 0.63];
+        let weights = [0.25; 4];
+        let (lo, hi) = bootstrap_interval(&probs, &weights, &LinearPool, 0.9);
+        let point = LinearPool.aggregate(&probs, &weights);
+        assert!(lo < point && point < hi);
+    }
+
+    #[test]
+    fn test_disagreement_widens_interval() {
+        let weights = [0.25; 4];
+        let (lo, hi) = bootstrap_interval(&[0.60, 0.62, 0.61, 0.63], &weights, &LinearPool, 0.9);
+        let (wlo, whi) = bootstrap_interval(&[0.30, 0.85, 0.45, 0.90], &weights, &LinearPool, 0.9);
+        assert!(whi - wlo > 3.0 * (hi - lo));
+    }
+
+    #[test]
+    fn test_single_source_has_nonzero_width() {
+        let (lo, hi) = bootstrap_interval(&[0.5], &[1.0], &LinearPool, 0.9);
+        assert!(hi - lo > 0.02 && hi - lo < 0.15);
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let probs = [0.2, 0.5, 0.7];
+        let weights = [0.5, 0.3, 0.2];
+        assert_eq!(
+            bootstrap_interval(&probs, &weights, &LinearPool, 0.9),
+            bootstrap_interval(&probs, &weights, &LinearPool, 0.9),
+        );
+    }
+}
@@ -0,0 +1,254 @@
+//! Lead-lag and price discovery between two venues quoting the same market.
+//!
+//! Both odds histories are put on a common time grid (last observation
+//! carried forward) and differenced. The grid step is at least the slowest
+//! venue's polling cadence: on a finer grid a venue polled less often shows
+//! every move a step or more late, and would look like it follows. The cross-correlation of the changes at
+//! lag `k` is `corr(Δa_t, Δb_{t+k})`, so a peak at a positive lag means `a`
+//! moves first. A's price discovery share is the lead-lag ratio as a share:
+//! the squared correlations where `a` leads over those at every non-zero lag.
+//! 0.5 means neither venue leads.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Shortest grid step the odds histories are resampled to.
+pub const MIN_STEP_MINUTES: i64 = 10;
+
+/// Largest lag, in grid steps, either way.
+pub const DEFAULT_MAX_LAG: usize = 12;
+
+/// Fewest price changes on the grid for a pair to be analysed.
+pub const MIN_OBSERVATIONS: usize = 30;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct LagCorrelation {
+    /// Steps by which `a` leads `b` (negative: `b` leads).
+    pub lag: i32,
+    pub correlation: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeadLag {
+    /// Grid changes the correlations are computed from.
+    pub observations: usize,
+    /// Correlation at each lag from `-max_lag` to `max_lag`.
+    pub correlations: Vec<LagCorrelation>,
+    /// Lag with the largest correlation.
+    pub best_lag: i32,
+    pub best_correlation: f64,
+    /// A's price discovery share, 0 to 1.
+    pub a_share: f64,
+}
+
+/// Sample a time series on a regular grid from `start` to `end`, carrying the
+/// last observation forward. Points before the first observation take its
+/// value.
+pub fn resample(
+    points: &[(DateTime<Utc>, f64)],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    step: Duration,
+) -> Vec<f64> {
+    let Some(first) = points.first() else {
+        return Vec::new();
+    };
+    let mut grid = Vec::new();
+    let mut current = first.1;
+    let mut next = 0;
+    let mut t = start;
+    while t <= end {
+        while next < points.len() && points[next].0 <= t {
+            current = points[next].1;
+            next += 1;
+        }
+        grid.push(current);
+        t += step;
+    }
+    grid
+}
+
+/// Typical time between a series' observations: the median gap. `None` with
+/// fewer than two observations.
+pub fn median_gap(points: &[(DateTime<Utc>, f64)]) -> Option<Duration> {
+    let mut gaps: Vec<Duration> = points.windows(2).map(|w| w[1].0 - w[0].0).collect();
+    if gaps.is_empty() {
+        return None;
+    }
+    gaps.sort();
+    Some(gaps[gaps.len() / 2])
+}
+
+/// Grid step for venues observed at the given cadences: the slowest, rounded
+/// up to whole minutes and no shorter than [`MIN_STEP_MINUTES`].
+pub fn grid_step(cadences: impl IntoIterator<Item = Duration>) -> Duration {
+    let slowest = cadences.into_iter().max().unwrap_or_else(Duration::zero);
+    let minutes = (slowest.num_seconds() + 59) / 60;
+    Duration::minutes(minutes.max(MIN_STEP_MINUTES))
+}
+
+/// Cross-correlate the changes of two series sampled on the same grid. `None`
+/// when there are fewer than [`MIN_OBSERVATIONS`] changes or either series
+/// never moves.
+pub fn lead_lag(a: &[f64], b: &[f64], max_lag: usize) -> Option<LeadLag> {
+    let n = a.len().min(b.len());
+    if n < 2 {
+        return None;
+    }
+    let da: Vec<f64> = a[..n].windows(2).map(|w| w[1] - w[0]).collect();
+    let db: Vec<f64> = b[..n].windows(2).map(|w| w[1] - w[0]).collect();
+    let moves = da.iter().chain(db.iter()).filter(|d| **d != 0.0).count();
+    if da.len() < MIN_OBSERVATIONS || moves < MIN_OBSERVATIONS {
+        return None;
+    }
+    let max_lag = max_lag.min(da.len() / 2);
+
+    let mut correlations = Vec::with_capacity(2 * max_lag + 1);
+    for lag in -(max_lag as i32)..=(max_lag as i32) {
+        let (xs, ys) = if lag >= 0 {
+            (&da[..da.len() - lag as usize], &db[lag as usize..])
+        } else {
+            (&da[(-lag) as usize..], &db[..db.len() - (-lag) as usize])
+        };
+        correlations.push(LagCorrelation {
+            lag,
+            correlation: pearson(xs, ys)?,
+        });
+    }
+    Some(summarize(da.len(), correlations))
+}
+
+/// Pool per-market results into one, weighting each by its observations.
+pub fn pooled(results: &[LeadLag]) -> Option<LeadLag> {
+    let total: usize = results.iter().map(|r| r.observations).sum();
+    let first = results.first()?;
+    if total == 0 {
+        return None;
+    }
+    let correlations = first.correlations.iter()
+        .map(|c| {
+            let weighted: f64 = results.iter()
+                .filter_map(|r| {
+                    r.correlations.iter()
+                        .find(|o| o.lag == c.lag)
+                        .map(|o| o.correlation * r.observations as f64)
+                })
+                .sum();
+            LagCorrelation { lag: c.lag, correlation: weighted / total as f64 }
+        })
+        .collect();
+    let mut result = summarize(total, correlations);
+    result.a_share = results.iter()
+        .map(|r| r.a_share * r.observations as f64)
+        .sum::<f64>() / total as f64;
+    Some(result)
+}
+
+fn summarize(observations: usize, correlations: Vec<LagCorrelation>) -> LeadLag {
+    let best = correlations.iter()
+        .copied()
+        .max_by(|x, y| x.correlation.partial_cmp(&y.correlation).unwrap_or(std::cmp::Ordering::Equal))
+        .unwrap_or(LagCorrelation { lag: 0, correlation: 0.0 });
+    let leads: f64 = correlations.iter().filter(|c| c.lag > 0).map(|c| c.correlation.powi(2)).sum();
+    let lags: f64 = correlations.iter().filter(|c| c.lag < 0).map(|c| c.correlation.powi(2)).sum();
+    LeadLag {
+        observations,
+        best_lag: best.lag,
+        best_correlation: best.correlation,
+        a_share: if leads + lags > 0.0 { leads / (leads + lags) } else { 0.5 },
+        correlations,
+    }
+}
+
+pub(crate) fn pearson(xs: &[f64], ys: &[f64]) -> Option<f64> {
+    let n = xs.len() as f64;
+    if n < 2.0 {
+        return None;
+    }
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = ys.iter().sum::<f64>() / n;
+    let (mut cov, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for (x, y) in xs.iter().zip(ys) {
+        cov += (x - mean_x) * (y - mean_y);
+        var_x += (x - mean_x).powi(2);
+        var_y += (y - mean_y).powi(2);
+    }
+    if var_x <= 0.0 || var_y <= 0.0 {
+        return None;
+    }
+    Some(cov / (var_x * var_y).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A deterministic random walk.
+    fn walk(n: usize, seed: u64) -> Vec<f64> {
+        let mut state = seed;
+        let mut p = 0.5;
+        (0..n)
+            .map(|_| {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                let step = ((state >> 33) as f64 / (1u64 << 31) as f64 - 0.5) * 0.02;
+                p = (p + step).clamp(0.01, 0.99);
+                p
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_leader_is_found() {
+        // b follows a three steps later
+        let a = walk(400, 7);
+        let b: Vec<f64> = std::iter::repeat_n(a[0], 3).chain(a[..397].iter().copied()).collect();
+        let result = lead_lag(&a, &b, 6).unwrap();
+        assert_eq!(result.best_lag, 3);
+        assert!(result.best_correlation > 0.99);
+        assert!(result.a_share > 0.95);
+
+        let reversed = lead_lag(&b, &a, 6).unwrap();
+        assert_eq!(reversed.best_lag, -3);
+        assert!(reversed.a_share < 0.05);
+    }
+
+    #[test]
+    fn test_flat_series_is_skipped() {
+        let a = walk(200, 3);
+        assert!(lead_lag(&a, &[0.5; 200], 6).is_none());
+        assert!(lead_lag(&a[..10], &a[..10], 6).is_none());
+    }
+
+    #[test]
+    fn test_resample_and_pool() {
+        let t0 = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let points = [(t0 + Duration::minutes(5), 0.4), (t0 + Duration::minutes(25), 0.6)];
+        let grid = resample(&points, t0, t0 + Duration::minutes(30), Duration::minutes(10));
+        assert_eq!(grid, vec![0.4, 0.4, 0.4, 0.6]);
+
+        let a = walk(400, 11);
+        let b: Vec<f64> = std::iter::once(a[0]).chain(a[..399].iter().copied()).collect();
+        let one = lead_lag(&a, &b, 4).unwrap();
+        let two = lead_lag(&b, &a, 4).unwrap();
+        let both = pooled(&[one.clone(), one.clone(), two]).unwrap();
+        assert_eq!(both.observations, 3 * one.observations);
+        assert!((both.a_share - (2.0 * one.a_share + (1.0 - one.a_share)) / 3.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_grid_step_covers_slowest_cadence() {
+        let t0 = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        // Polled every 10 minutes plus a few seconds of fetching, one outage
+        let polls: Vec<(DateTime<Utc>, f64)> = [0, 605, 1210, 1815, 5400, 6005]
+            .iter()
+            .map(|s| (t0 + Duration::seconds(*s), 0.5))
+            .collect();
+        let slow = median_gap(&polls).unwrap();
+        assert_eq!(slow, Duration::seconds(605));
+        assert!(median_gap(&polls[..1]).is_none());
+
+        assert_eq!(grid_step([Duration::minutes(5), slow]), Duration::minutes(11));
+        assert_eq!(grid_step([Duration::minutes(5)]), Duration::minutes(MIN_STEP_MINUTES));
+        assert_eq!(grid_step([]), Duration::minutes(MIN_STEP_MINUTES));
+    }
+}
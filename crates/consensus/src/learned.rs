@@ -0,0 +1,270 @@
+//! Source weights learned from resolved markets.
+//!
+//! Each source gets a log-weight `θ_s`. On a market quoted by sources `S`, the
+//! weights are a softmax of their `θ` and the forecast is the linear pool
+//! `p = Σ w_s p_s`. The `θ` minimize mean Brier or log loss over resolved
+//! markets plus an L2 penalty `λ Σ θ_s²`, which pulls every source toward
+//! `θ = 0`, i.e. equal weights. `λ` is chosen by k-fold cross-validation, so the
+//! reported loss is out of sample.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Weights are fitted on each source's odds this long before resolution, when
+/// the outcome is not yet priced in.
+pub const WEIGHT_FIT_HORIZON_HOURS: i64 = 24;
+
+/// Minimum resolved markets with two or more sources before fitting.
+pub const MIN_SAMPLES_FOR_WEIGHT_FIT: usize = 50;
+
+/// Regularization strengths tried during cross-validation.
+const LAMBDA_GRID: [f64; 5] = [0.001, 0.01, 0.03, 0.1, 1.0];
+
+const CV_FOLDS: usize = 5;
+
+/// Log-weights are kept within this range so no source is zeroed out.
+const THETA_BOUND: f64 = 3.0;
+
+const PROB_EPSILON: f64 = 1e-4;
+
+/// Loss minimized when fitting.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FitObjective {
+    Brier,
+    #[default]
+    LogLoss,
+}
+
+impl FitObjective {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FitObjective::Brier => "brier",
+            FitObjective::LogLoss => "log_loss",
+        }
+    }
+
+    fn loss(&self, p: f64, y: f64) -> f64 {
+        match self {
+            FitObjective::Brier => (p - y).powi(2),
+            FitObjective::LogLoss => {
+                let p = p.clamp(PROB_EPSILON, 1.0 - PROB_EPSILON);
+                -(y * p.ln() + (1.0 - y) * (1.0 - p).ln())
+            }
+        }
+    }
+
+    /// Derivative of the loss with respect to `p`.
+    fn d_loss(&self, p: f64, y: f64) -> f64 {
+        match self {
+            FitObjective::Brier => 2.0 * (p - y),
+            FitObjective::LogLoss => {
+                let p = p.clamp(PROB_EPSILON, 1.0 - PROB_EPSILON);
+                (p - y) / (p * (1.0 - p))
+            }
+        }
+    }
+}
+
+/// One resolved market: each source's probability and the outcome.
+#[derive(Debug, Clone)]
+pub struct WeightSample {
+    pub forecasts: Vec<(String, f64)>,
+    pub outcome: f64,
+}
+
+/// A fitted weight set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LearnedWeights {
+    /// Relative weight per source id, scaled so their mean is 1.
+    pub weights: BTreeMap<String, f64>,
+    pub objective: FitObjective,
+    pub regularization: f64,
+    pub sample_size: usize,
+    /// Cross-validated loss at the chosen regularization.
+    pub cv_loss: f64,
+    /// Loss of equal weights on the same markets.
+    pub baseline_loss: f64,
+}
+
+impl LearnedWeights {
+    /// Whether the fitted weights beat equal weights out of sample.
+    pub fn beats_baseline(&self) -> bool {
+        self.cv_loss < self.baseline_loss
+    }
+}
+
+/// Fit per-source weights. Markets with fewer than two sources carry no
+/// information about relative weights and are ignored. Returns `None` below
+/// [`MIN_SAMPLES_FOR_WEIGHT_FIT`] usable markets.
+pub fn fit_source_weights(samples: &[WeightSample], objective: FitObjective) -> Option<LearnedWeights> {
+    let samples: Vec<&WeightSample> = samples.iter().filter(|s| s.forecasts.len() >= 2).collect();
+    if samples.len() < MIN_SAMPLES_FOR_WEIGHT_FIT {
+        return None;
+    }
+
+    let ids: Vec<String> = samples.iter()
+        .flat_map(|s| s.forecasts.iter().map(|(id, _)| id.clone()))
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let indexed: Vec<IndexedSample> = samples.iter()
+        .map(|s| IndexedSample {
+            forecasts: s.forecasts.iter()
+                .map(|(id, p)| (ids.binary_search(id).unwrap_or_default(), *p))
+                .collect(),
+            outcome: s.outcome,
+        })
+        .collect();
+
+    let equal = vec![0.0; ids.len()];
+    let baseline_loss = mean_loss(&indexed, &equal, objective);
+
+    let (regularization, cv_loss) = LAMBDA_GRID.iter()
+        .map(|&lambda| (lambda, cross_validate(&indexed, ids.len(), lambda, objective)))
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))?;
+
+    let theta = fit_theta(&indexed, ids.len(), regularization, objective);
+    let raw: Vec<f64> = theta.iter().map(|t| t.exp()).collect();
+    let mean = raw.iter().sum::<f64>() / raw.len() as f64;
+    let weights = ids.into_iter().zip(raw.iter().map(|w| w / mean)).collect();
+
+    Some(LearnedWeights {
+        weights,
+        objective,
+        regularization,
+        sample_size: indexed.len(),
+        cv_loss,
+        baseline_loss,
+    })
+}
+
+#[derive(Clone)]
+struct IndexedSample {
+    forecasts: Vec<(usize, f64)>,
+    outcome: f64,
+}
+
+fn pooled(sample: &IndexedSample, theta: &[f64]) -> (f64, Vec<f64>) {
+    let raw: Vec<f64> = sample.forecasts.iter().map(|(i, _)| theta[*i].exp()).collect();
+    let total: f64 = raw.iter().sum();
+    let weights: Vec<f64> = raw.iter().map(|w| w / total).collect();
+    let p = sample.forecasts.iter().zip(&weights).map(|((_, p), w)| p * w).sum();
+    (p, weights)
+}
+
+fn mean_loss(samples: &[IndexedSample], theta: &[f64], objective: FitObjective) -> f64 {
+    samples.iter()
+        .map(|s| objective.loss(pooled(s, theta).0, s.outcome))
+        .sum::<f64>() / samples.len() as f64
+}
+
+fn penalized_loss(samples: &[IndexedSample], theta: &[f64], lambda: f64, objective: FitObjective) -> f64 {
+    mean_loss(samples, theta, objective) + lambda * theta.iter().map(|t| t * t).sum::<f64>()
+}
+
+/// Gradient descent with step halving on the penalized loss.
+fn fit_theta(samples: &[IndexedSample], n: usize, lambda: f64, objective: FitObjective) -> Vec<f64> {
+    let mut theta = vec![0.0; n];
+    let mut loss = penalized_loss(samples, &theta, lambda, objective);
+    let mut step = 1.0;
+
+    for _ in 0..500 {
+        let mut gradient: Vec<f64> = theta.iter().map(|t| 2.0 * lambda * t).collect();
+        for s in samples {
+            let (p, weights) = pooled(s, &theta);
+            let d = objective.d_loss(p, s.outcome) / samples.len() as f64;
+            for ((i, p_i), w) in s.forecasts.iter().zip(&weights) {
+                gradient[*i] += d * w * (p_i - p);
+            }
+        }
+        if gradient.iter().map(|g| g * g).sum::<f64>().sqrt() < 1e-9 {
+            break;
+        }
+
+        loop {
+            let candidate: Vec<f64> = theta.iter()
+                .zip(&gradient)
+                .map(|(t, g)| (t - step * g).clamp(-THETA_BOUND, THETA_BOUND))
+                .collect();
+            let candidate_loss = penalized_loss(samples, &candidate, lambda, objective);
+            if candidate_loss <= loss {
+                theta = candidate;
+                loss = candidate_loss;
+                step *= 1.5;
+                break;
+            }
+            step /= 2.0;
+            if step < 1e-10 {
+                return theta;
+            }
+        }
+    }
+    theta
+}
+
+fn cross_validate(samples: &[IndexedSample], n: usize, lambda: f64, objective: FitObjective) -> f64 {
+    let mut total = 0.0;
+    for fold in 0..CV_FOLDS {
+        let (test, train): (Vec<_>, Vec<_>) = samples.iter()
+            .enumerate()
+            .partition(|(i, _)| i % CV_FOLDS == fold);
+        let train: Vec<IndexedSample> = train.into_iter().map(|(_, s)| s.clone()).collect();
+        let theta = fit_theta(&train, n, lambda, objective);
+        total += test.iter()
+            .map(|(_, s)| objective.loss(pooled(s, &theta).0, s.outcome))
+            .sum::<f64>();
+    }
+    total / samples.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// "sharp" forecasts 0.8 on the outcome, "noisy" forecasts 0.5 throughout.
+    fn samples(n: usize) -> Vec<WeightSample> {
+        (0..n)
+            .map(|i| {
+                let outcome = (i % 2) as f64;
+                WeightSample {
+                    forecasts: vec![
+                        ("sharp".into(), if outcome == 1.0 { 0.8 } else { 0.2 }),
+                        ("noisy".into(), 0.5),
+                    ],
+                    outcome,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_learns_to_trust_better_source() {
+        let fit = fit_source_weights(&samples(100), FitObjective::LogLoss).unwrap();
+        assert!(fit.weights["sharp"] > fit.weights["noisy"]);
+        assert!(fit.beats_baseline());
+        let mean: f64 = fit.weights.values().sum::<f64>() / fit.weights.len() as f64;
+        assert!((mean - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_indistinguishable_sources_stay_equal() {
+        let samples: Vec<WeightSample> = (0..100)
+            .map(|i| WeightSample {
+                forecasts: vec![("a".into(), 0.7), ("b".into(), 0.7)],
+                outcome: (i % 3 != 0) as i32 as f64,
+            })
+            .collect();
+        let fit = fit_source_weights(&samples, FitObjective::Brier).unwrap();
+        assert!((fit.weights["a"] - fit.weights["b"]).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_needs_enough_multi_source_markets() {
+        assert!(fit_source_weights(&samples(20), FitObjective::LogLoss).is_none());
+        let single: Vec<WeightSample> = (0..100)
+            .map(|_| WeightSample { forecasts: vec![("a".into(), 0.6)], outcome: 1.0 })
+            .collect();
+        assert!(fit_source_weights(&single, FitObjective::LogLoss).is_none());
+    }
+}
@@ -1,2 +1,19 @@
+pub mod aggregator;
+pub mod arbitrage;
+pub mod backtest;
 pub mod brier;
+pub mod calibration;
+pub mod comparison;
+pub mod dependence;
 pub mod engine;
+pub mod explain;
+pub mod extremize;
+pub mod interval;
+pub mod leadlag;
+pub mod learned;
+pub mod liquidity;
+pub mod outlier;
+pub mod recalibration;
+pub mod scoring;
+#[cfg(feature = "store")]
+pub mod store;
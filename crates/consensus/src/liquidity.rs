@@ -0,0 +1,188 @@
+//! Liquidity and depth weighting.
+//!
+//! Thin markets are cheap to move, so a source's weight is scaled by how deep
+//! its market is. Each depth signal is scored on a log scale between a floor
+//! (score 0) and a saturation point (score 1), so returns diminish quickly:
+//! going from $1k to $10k matters far more than going from $10M to $100M.
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{ConsensusConfig, SourceInput};
+
+/// Traded volume (USD) scoring 0 and 1.
+const VOLUME_RANGE: (f64, f64) = (1_000.0, 1_000_000.0);
+
+/// Order book / AMM liquidity (USD) scoring 0 and 1.
+const LIQUIDITY_RANGE: (f64, f64) = (500.0, 250_000.0);
+
+/// Forecaster counts scoring 0 and 1, for forecasting platforms like Metaculus.
+const FORECASTER_RANGE: (f64, f64) = (10.0, 500.0);
+
+/// Depth score given to a source with no depth signal at all: halfway, so
+/// missing data neither beats a real deep market nor sinks to the floor.
+pub const UNKNOWN_DEPTH_SCORE: f64 = 0.5;
+
+/// Bid-ask spread (probability points) at which the spread factor falls to 1/e.
+const SPREAD_SCALE: f64 = 0.10;
+
+/// Per-source breakdown of the liquidity multiplier.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiquidityBreakdown {
+    pub volume_score: Option<f64>,
+    pub liquidity_score: Option<f64>,
+    pub forecaster_score: Option<f64>,
+    /// Penalty for a wide bid-ask spread (1 = no penalty).
+    pub spread_factor: f64,
+    /// Final multiplier applied to the source's weight.
+    pub factor: f64,
+}
+
+impl Default for LiquidityBreakdown {
+    fn default() -> Self {
+        Self {
+            volume_score: None,
+            liquidity_score: None,
+            forecaster_score: None,
+            spread_factor: 1.0,
+            factor: 1.0,
+        }
+    }
+}
+
+/// Score a depth signal between 0 and 1 on a log scale.
+fn log_score(value: f64, (floor, full): (f64, f64)) -> f64 {
+    if value <= floor {
+        return 0.0;
+    }
+    ((value / floor).ln() / (full / floor).ln()).clamp(0.0, 1.0)
+}
+
+/// Compute the liquidity multiplier for a source.
+///
+/// The depth score is the mean of the available volume, liquidity and
+/// forecaster-count scores and maps onto `[min_liquidity_factor, 1]`. Sources
+/// with no depth information at all score [`UNKNOWN_DEPTH_SCORE`], except
+/// play-money sources whose volume and liquidity have no USD value, which get
+/// `play_money_liquidity_factor`. A wide spread then scales the result down
+/// further.
+pub fn liquidity_factor(source: &SourceInput, config: &ConsensusConfig) -> LiquidityBreakdown {
+    if !config.liquidity_weighting {
+        return LiquidityBreakdown::default();
+    }
+
+    let volume_score = source.volume.map(|v| log_score(v, VOLUME_RANGE));
+    let liquidity_score = source.liquidity.map(|l| log_score(l, LIQUIDITY_RANGE));
+    let forecaster_score = source.forecaster_count.map(|n| log_score(n as f64, FORECASTER_RANGE));
+
+    let scores: Vec<f64> = [volume_score, liquidity_score, forecaster_score]
+        .into_iter()
+        .flatten()
+        .collect();
+    let depth = if scores.is_empty() {
+        UNKNOWN_DEPTH_SCORE
+    } else {
+        scores.iter().sum::<f64>() / scores.len() as f64
+    };
+    let floor = config.min_liquidity_factor.clamp(0.0, 1.0);
+    let depth_factor = if source.play_money && volume_score.is_none() && liquidity_score.is_none() {
+        config.play_money_liquidity_factor.clamp(0.0, 1.0)
+    } else {
+        floor + (1.0 - floor) * depth
+    };
+
+    let spread_factor = source.spread
+        .map(|s| (-s.max(0.0) / SPREAD_SCALE).exp())
+        .unwrap_or(1.0);
+
+    LiquidityBreakdown {
+        volume_score,
+        liquidity_score,
+        forecaster_score,
+        spread_factor,
+        factor: depth_factor * spread_factor,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn market(volume: Option<f64>, liquidity: Option<f64>) -> SourceInput {
+        SourceInput {
+            source_id: "pm".into(),
+            source_name: "Polymarket".into(),
+            probability: 0.5,
+            volume,
+            liquidity,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_thin_market_gets_floor() {
+        let config = ConsensusConfig::default();
+        let thin = liquidity_factor(&market(Some(500.0), Some(100.0)), &config);
+        assert!((thin.factor - config.min_liquidity_factor).abs() < 1e-10);
+
+        let deep = liquidity_factor(&market(Some(50_000_000.0), Some(2_000_000.0)), &config);
+        assert!((deep.factor - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_diminishing_returns() {
+        let config = ConsensusConfig::default();
+        let f = |v: f64| liquidity_factor(&market(Some(v), None), &config).factor;
+        assert!(f(10_000.0) - f(1_000.0) > f(1_000_000.0) - f(100_000.0) - 1e-12);
+        assert!(f(100_000.0) < f(1_000_000.0));
+    }
+
+    #[test]
+    fn test_spread_penalty_and_unknown_depth() {
+        let config = ConsensusConfig::default();
+        let mut source = market(None, None);
+        let unknown = liquidity_factor(&source, &config).factor;
+        assert!((unknown - 0.6).abs() < 1e-10);
+        // Missing data does not beat a real, reasonably deep market
+        assert!(unknown < liquidity_factor(&market(Some(100_000.0), Some(50_000.0)), &config).factor);
+
+        source.spread = Some(0.10);
+        let breakdown = liquidity_factor(&source, &config);
+        assert!((breakdown.spread_factor - (-1.0f64).exp()).abs() < 1e-10);
+        assert!((breakdown.factor - unknown * (-1.0f64).exp()).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_play_money_without_usd_depth() {
+        let config = ConsensusConfig::default();
+        let mut manifold = market(None, None);
+        manifold.play_money = true;
+        let breakdown = liquidity_factor(&manifold, &config);
+        assert!((breakdown.factor - config.play_money_liquidity_factor).abs() < 1e-10);
+        // No better off than a thin real-money market
+        let thin = liquidity_factor(&market(Some(500.0), Some(100.0)), &config);
+        assert!(breakdown.factor <= thin.factor);
+
+        let config = ConsensusConfig { play_money_liquidity_factor: 0.5, ..ConsensusConfig::default() };
+        assert!((liquidity_factor(&manifold, &config).factor - 0.5).abs() < 1e-10);
+
+        // A rate for the play currency restores ordinary depth scoring
+        manifold.volume = Some(1_000_000.0);
+        assert!((liquidity_factor(&manifold, &ConsensusConfig::default()).factor - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_forecaster_count() {
+        let config = ConsensusConfig::default();
+        let mut source = market(None, None);
+        source.forecaster_count = Some(500);
+        assert!((liquidity_factor(&source, &config).factor - 1.0).abs() < 1e-10);
+        source.forecaster_count = Some(5);
+        assert!((liquidity_factor(&source, &config).factor - config.min_liquidity_factor).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_disabled() {
+        let config = ConsensusConfig { liquidity_weighting: false, ..ConsensusConfig::default() };
+        assert_eq!(liquidity_factor(&market(Some(10.0), None), &config).factor, 1.0);
+    }
+}
@@ -0,0 +1,196 @@
+//! Robust outlier detection.
+//!
+//! Each source is compared with a reference that it cannot drag along: the
+//! weighted median of all sources, or the consensus of the other sources
+//! (leave-one-out). Deviations are scaled by the median absolute deviation
+//! (MAD) of the source probabilities, so "far" adapts to how much the sources
+//! normally disagree on the market. Flagged sources can be reported only,
+//! down-weighted, or excluded.
+
+use serde::{Deserialize, Serialize};
+
+use crate::aggregator::{Aggregator, WeightedMedian};
+use crate::engine::ConsensusConfig;
+
+/// Scale factor making the MAD a consistent estimate of a standard deviation.
+const MAD_TO_SD: f64 = 1.4826;
+
+/// Floor on the MAD scale, so near-unanimous markets do not flag tiny deviations.
+const MIN_SCALE: f64 = 0.02;
+
+/// Fewer sources than this cannot tell which one is off.
+pub const MIN_SOURCES_FOR_OUTLIERS: usize = 3;
+
+/// What each source is compared against.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutlierReference {
+    /// Weighted median of all sources.
+    #[default]
+    Median,
+    /// Consensus of the other sources under the configured aggregator.
+    LeaveOneOut,
+}
+
+/// What happens to a flagged source.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutlierMode {
+    /// Listed in the result only.
+    #[default]
+    Report,
+    /// Weight scaled by `(threshold / z)²`.
+    Downweight,
+    /// Weight set to zero.
+    Exclude,
+}
+
+/// The outcome of checking one source.
+#[derive(Debug, Clone, Copy)]
+pub struct OutlierCheck {
+    pub reference: f64,
+    pub deviation: f64,
+    /// Deviation in MAD-scaled units.
+    pub robust_z: f64,
+    pub is_outlier: bool,
+}
+
+/// Check every source against the configured reference. A source is flagged
+/// when it is more than `outlier_mad_threshold` scaled MADs and at least
+/// `outlier_threshold` away from it.
+pub fn detect_outliers(
+    probabilities: &[f64],
+    weights: &[f64],
+    aggregator: &dyn Aggregator,
+    config: &ConsensusConfig,
+) -> Vec<OutlierCheck> {
+    let median = WeightedMedian.aggregate(probabilities, weights);
+    let deviations: Vec<f64> = probabilities.iter().map(|p| (p - median).abs()).collect();
+    let scale = (MAD_TO_SD * plain_median(&deviations)).max(MIN_SCALE);
+    let enough = probabilities.len() >= MIN_SOURCES_FOR_OUTLIERS;
+
+    probabilities.iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let reference = match config.outlier_reference {
+                OutlierReference::Median => median,
+                OutlierReference::LeaveOneOut => leave_one_out(probabilities, weights, i, aggregator),
+            };
+            let deviation = (p - reference).abs();
+            let robust_z = deviation / scale;
+            OutlierCheck {
+                reference,
+                deviation,
+                robust_z,
+                is_outlier: enough
+                    && robust_z > config.outlier_mad_threshold
+                    && deviation > config.outlier_threshold,
+            }
+        })
+        .collect()
+}
+
+/// Multiplier applied to a source's weight under the configured mode.
+pub fn weight_factor(check: &OutlierCheck, config: &ConsensusConfig) -> f64 {
+    if !check.is_outlier {
+        return 1.0;
+    }
+    match config.outlier_mode {
+        OutlierMode::Report => 1.0,
+        OutlierMode::Downweight => (config.outlier_mad_threshold / check.robust_z).powi(2).min(1.0),
+        OutlierMode::Exclude => 0.0,
+    }
+}
+
+/// Human-readable reason a source was flagged.
+pub fn reason(check: &OutlierCheck, config: &ConsensusConfig) -> String {
+    let reference = match config.outlier_reference {
+        OutlierReference::Median => "weighted median",
+        OutlierReference::LeaveOneOut => "consensus of the other sources",
+    };
+    format!(
+        "{:.1} MADs ({:.1} points) from the {} at {:.1}%",
+        check.robust_z,
+        check.deviation * 100.0,
+        reference,
+        check.reference * 100.0
+    )
+}
+
+fn leave_one_out(probabilities: &[f64], weights: &[f64], skip: usize, aggregator: &dyn Aggregator) -> f64 {
+    let (ps, ws): (Vec<f64>, Vec<f64>) = probabilities.iter()
+        .zip(weights)
+        .enumerate()
+        .filter(|(j, _)| *j != skip)
+        .map(|(_, (p, w))| (*p, *w))
+        .unzip();
+    let total: f64 = ws.iter().sum();
+    let ws: Vec<f64> = if total > 0.0 {
+        ws.iter().map(|w| w / total).collect()
+    } else {
+        vec![1.0 / ws.len() as f64; ws.len()]
+    };
+    aggregator.aggregate(&ps, &ws)
+}
+
+fn plain_median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let n = sorted.len();
+    if n == 0 {
+        0.0
+    } else if n % 2 == 1 {
+        sorted[n / 2]
+    } else {
+        (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aggregator::LinearPool;
+
+    const PROBS: [f64; 4] = [0.62, 0.60, 0.64, 0.15];
+    const WEIGHTS: [f64; 4] = [0.25; 4];
+
+    #[test]
+    fn test_flags_only_the_broken_source() {
+        let config = ConsensusConfig::default();
+        let checks = detect_outliers(&PROBS, &WEIGHTS, &LinearPool, &config);
+        let flagged: Vec<usize> = checks.iter().enumerate().filter(|(_, c)| c.is_outlier).map(|(i, _)| i).collect();
+        assert_eq!(flagged, vec![3]);
+    }
+
+    #[test]
+    fn test_leave_one_out_reference() {
+        let config = ConsensusConfig {
+            outlier_reference: OutlierReference::LeaveOneOut,
+            ..ConsensusConfig::default()
+        };
+        let checks = detect_outliers(&PROBS, &WEIGHTS, &LinearPool, &config);
+        assert!((checks[3].reference - 0.62).abs() < 1e-10);
+        assert!(checks[3].is_outlier);
+    }
+
+    #[test]
+    fn test_modes() {
+        let mut config = ConsensusConfig::default();
+        let checks = detect_outliers(&PROBS, &WEIGHTS, &LinearPool, &config);
+        assert_eq!(weight_factor(&checks[3], &config), 1.0);
+
+        config.outlier_mode = OutlierMode::Downweight;
+        let factor = weight_factor(&checks[3], &config);
+        assert!(factor > 0.0 && factor < 0.1);
+        assert_eq!(weight_factor(&checks[0], &config), 1.0);
+
+        config.outlier_mode = OutlierMode::Exclude;
+        assert_eq!(weight_factor(&checks[3], &config), 0.0);
+    }
+
+    #[test]
+    fn test_two_sources_never_flagged() {
+        let checks = detect_outliers(&[0.9, 0.1], &[0.5, 0.5], &LinearPool, &ConsensusConfig::default());
+        assert!(checks.iter().all(|c| !c.is_outlier));
+    }
+}
@@ -0,0 +1,289 @@
+//! Per-source recalibration of quoted probabilities.
+//!
+//! Venues are miscalibrated in characteristic ways: real-money markets tend to
+//! overprice longshots, play-money markets drift toward the extremes or away
+//! from them. Each source gets a curve mapping its quoted probability to the
+//! frequency with which such quotes resolved YES, fitted on its resolved
+//! history, and the consensus pools the corrected probabilities.
+//!
+//! Two curves are tried: Platt scaling, a logistic regression on the quote's
+//! log-odds (`logit p' = slope * logit p + intercept`), and isotonic
+//! regression, a monotone step fit interpolated linearly. Whichever has the
+//! lower cross-validated log loss is kept, and only used when it also beats
+//! the uncorrected quotes.
+
+use serde::{Deserialize, Serialize};
+
+use crate::aggregator::{logit, sigmoid};
+
+/// Minimum resolved markets from a source before a curve is fitted.
+pub const MIN_SAMPLES_FOR_RECALIBRATION: usize = 100;
+
+/// Corrected probabilities are kept this far from 0 and 1, so a sparse end of
+/// an isotonic fit cannot declare an outcome certain.
+pub const PROBABILITY_FLOOR: f64 = 0.01;
+
+const CV_FOLDS: usize = 5;
+
+/// Platt slopes are kept within this range.
+const SLOPE_BOUNDS: (f64, f64) = (0.2, 5.0);
+
+const INTERCEPT_BOUND: f64 = 3.0;
+
+/// A fitted recalibration curve.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum Recalibration {
+    /// `logit p' = slope * logit p + intercept`. A slope below 1 pulls quotes
+    /// toward 50%, correcting overpriced longshots and favourites.
+    Platt { slope: f64, intercept: f64 },
+    /// `(quoted, corrected)` knots in increasing order of both, interpolated
+    /// linearly and held flat beyond the ends.
+    Isotonic { points: Vec<(f64, f64)> },
+}
+
+impl Recalibration {
+    pub fn method(&self) -> &'static str {
+        match self {
+            Recalibration::Platt { .. } => "platt",
+            Recalibration::Isotonic { .. } => "isotonic",
+        }
+    }
+
+    /// The corrected probability for a quote of `p`.
+    pub fn apply(&self, p: f64) -> f64 {
+        let corrected = match self {
+            Recalibration::Platt { slope, intercept } => sigmoid(slope * logit(p) + intercept),
+            Recalibration::Isotonic { points } => interpolate(points, p),
+        };
+        corrected.clamp(PROBABILITY_FLOOR, 1.0 - PROBABILITY_FLOOR)
+    }
+}
+
+/// A fitted curve and how it scored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecalibrationFit {
+    pub recalibration: Recalibration,
+    /// Resolved markets the curve was fitted on.
+    pub sample_size: usize,
+    /// Cross-validated log loss of the corrected probabilities.
+    pub cv_loss: f64,
+    /// Log loss of the quotes as-is on the same forecasts.
+    pub baseline_loss: f64,
+}
+
+impl RecalibrationFit {
+    /// Whether the correction beats the raw quotes out of sample.
+    pub fn beats_baseline(&self) -> bool {
+        self.cv_loss < self.baseline_loss
+    }
+}
+
+/// Fit a curve to `(quoted probability, outcome)` pairs grouped by market,
+/// choosing between Platt and isotonic by k-fold cross-validation. A market's
+/// forecasts at several horizons share an outcome, so they are kept in the
+/// same fold. Returns `None` below [`MIN_SAMPLES_FOR_RECALIBRATION`] markets.
+pub fn fit_recalibration(markets: &[Vec<(f64, f64)>]) -> Option<RecalibrationFit> {
+    if markets.len() < MIN_SAMPLES_FOR_RECALIBRATION {
+        return None;
+    }
+
+    let samples = markets.concat();
+    let baseline_loss = mean_log_loss(&samples, |p| p);
+    let platt_loss = cross_validate(markets, fit_platt);
+    let isotonic_loss = cross_validate(markets, fit_isotonic);
+    let (recalibration, cv_loss) = if platt_loss <= isotonic_loss {
+        (fit_platt(&samples), platt_loss)
+    } else {
+        (fit_isotonic(&samples), isotonic_loss)
+    };
+
+    Some(RecalibrationFit {
+        recalibration,
+        sample_size: markets.len(),
+        cv_loss,
+        baseline_loss,
+    })
+}
+
+/// Logistic regression of the outcome on the quote's log-odds, by Newton's
+/// method from the identity curve.
+pub fn fit_platt(samples: &[(f64, f64)]) -> Recalibration {
+    let xs: Vec<(f64, f64)> = samples.iter().map(|(p, y)| (logit(*p), *y)).collect();
+    let (mut slope, mut intercept) = (1.0, 0.0);
+    for _ in 0..50 {
+        let (mut g_s, mut g_i) = (0.0, 0.0);
+        let (mut h_ss, mut h_si, mut h_ii) = (0.0, 0.0, 0.0);
+        for (x, y) in &xs {
+            let q = sigmoid(slope * x + intercept);
+            let r = q * (1.0 - q);
+            g_s += (q - y) * x;
+            g_i += q - y;
+            h_ss += r * x * x;
+            h_si += r * x;
+            h_ii += r;
+        }
+        let det = h_ss * h_ii - h_si * h_si;
+        if det.abs() <= 1e-12 {
+            break;
+        }
+        let step_s = (h_ii * g_s - h_si * g_i) / det;
+        let step_i = (h_ss * g_i - h_si * g_s) / det;
+        slope = (slope - step_s).clamp(SLOPE_BOUNDS.0, SLOPE_BOUNDS.1);
+        intercept = (intercept - step_i).clamp(-INTERCEPT_BOUND, INTERCEPT_BOUND);
+        if step_s.abs() < 1e-8 && step_i.abs() < 1e-8 {
+            break;
+        }
+    }
+    Recalibration::Platt { slope, intercept }
+}
+
+/// Isotonic regression by pool-adjacent-violators. Each pooled block becomes
+/// a knot at its mean quote and mean outcome.
+pub fn fit_isotonic(samples: &[(f64, f64)]) -> Recalibration {
+    let mut sorted: Vec<(f64, f64)> = samples.to_vec();
+    sorted.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    // (sum of quotes, sum of outcomes, count)
+    let mut blocks: Vec<(f64, f64, f64)> = Vec::new();
+    for (p, y) in sorted {
+        blocks.push((p, y, 1.0));
+        while blocks.len() >= 2 {
+            let (p2, y2, n2) = blocks[blocks.len() - 1];
+            let (p1, y1, n1) = blocks[blocks.len() - 2];
+            if y1 / n1 < y2 / n2 {
+                break;
+            }
+            blocks.pop();
+            *blocks.last_mut().unwrap() = (p1 + p2, y1 + y2, n1 + n2);
+        }
+    }
+
+    Recalibration::Isotonic {
+        points: blocks.iter().map(|(p, y, n)| (p / n, y / n)).collect(),
+    }
+}
+
+fn interpolate(points: &[(f64, f64)], p: f64) -> f64 {
+    let (Some(first), Some(last)) = (points.first(), points.last()) else {
+        return p;
+    };
+    if p <= first.0 {
+        return first.1;
+    }
+    if p >= last.0 {
+        return last.1;
+    }
+    let i = points.partition_point(|(x, _)| *x <= p);
+    let (x0, y0) = points[i - 1];
+    let (x1, y1) = points[i];
+    if x1 - x0 <= f64::EPSILON {
+        y1
+    } else {
+        y0 + (y1 - y0) * (p - x0) / (x1 - x0)
+    }
+}
+
+/// Mean out-of-fold log loss per forecast. Folds interleave whole markets,
+/// so a curve is never scored on a market it was fitted on.
+fn cross_validate(markets: &[Vec<(f64, f64)>], fit: fn(&[(f64, f64)]) -> Recalibration) -> f64 {
+    let (mut total, mut count) = (0.0, 0);
+    for fold in 0..CV_FOLDS {
+        let (mut train, mut test) = (Vec::new(), Vec::new());
+        for (i, market) in markets.iter().enumerate() {
+            if i % CV_FOLDS == fold {
+                test.extend_from_slice(market)
+            } else {
+                train.extend_from_slice(market)
+            }
+        }
+        let curve = fit(&train);
+        total += mean_log_loss(&test, |p| curve.apply(p)) * test.len() as f64;
+        count += test.len();
+    }
+    total / count.max(1) as f64
+}
+
+fn mean_log_loss(samples: &[(f64, f64)], correct: impl Fn(f64) -> f64) -> f64 {
+    let total: f64 = samples.iter()
+        .map(|(p, y)| {
+            let q = correct(*p).clamp(1e-12, 1.0 - 1e-12);
+            -(y * q.ln() + (1.0 - y) * (1.0 - q).ln())
+        })
+        .sum();
+    total / samples.len().max(1) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Quotes from a venue whose true odds are `logit q = 0.6 * logit p`:
+    /// longshots and favourites both overpriced.
+    fn overconfident_samples(n: usize) -> Vec<(f64, f64)> {
+        (0..n)
+            .map(|i| {
+                let p = 0.02 + 0.96 * ((i * 37) % n) as f64 / n as f64;
+                let q = sigmoid(0.6 * logit(p));
+                // Deterministic outcomes at frequency q across neighbouring quotes
+                let y = if ((i * 7919) % 1000) as f64 / 1000.0 < q { 1.0 } else { 0.0 };
+                (p, y)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_platt_recovers_longshot_bias() {
+        let samples = overconfident_samples(4000);
+        let Recalibration::Platt { slope, intercept } = fit_platt(&samples) else {
+            panic!("expected a Platt curve");
+        };
+        assert!((slope - 0.6).abs() < 0.1, "slope {}", slope);
+        assert!(intercept.abs() < 0.2, "intercept {}", intercept);
+
+        let markets: Vec<Vec<(f64, f64)>> = samples.iter().map(|s| vec![*s]).collect();
+        let fit = fit_recalibration(&markets).unwrap();
+        assert!(fit.beats_baseline());
+        // Longshots pulled up, favourites pulled down
+        assert!(fit.recalibration.apply(0.05) > 0.05);
+        assert!(fit.recalibration.apply(0.95) < 0.95);
+    }
+
+    #[test]
+    fn test_isotonic_is_monotone_and_bounded() {
+        let samples = vec![(0.1, 0.0), (0.2, 1.0), (0.3, 0.0), (0.6, 1.0), (0.7, 0.0), (0.9, 1.0)];
+        let curve = fit_isotonic(&samples);
+        let Recalibration::Isotonic { points } = &curve else {
+            panic!("expected an isotonic curve");
+        };
+        assert!(points.windows(2).all(|w| w[0].1 <= w[1].1));
+        assert_eq!(curve.apply(0.0), PROBABILITY_FLOOR);
+        assert_eq!(curve.apply(1.0), 1.0 - PROBABILITY_FLOOR);
+        let mut previous = 0.0;
+        for i in 0..=100 {
+            let q = curve.apply(i as f64 / 100.0);
+            assert!(q >= previous);
+            previous = q;
+        }
+    }
+
+    #[test]
+    fn test_needs_enough_samples() {
+        // Markets are counted, not forecasts: four horizons each do not make
+        // up for too few markets
+        let markets: Vec<Vec<(f64, f64)>> = overconfident_samples(MIN_SAMPLES_FOR_RECALIBRATION - 1)
+            .into_iter()
+            .map(|(p, y)| vec![(p, y); 4])
+            .collect();
+        assert!(fit_recalibration(&markets).is_none());
+    }
+
+    #[test]
+    fn test_stored_curve_parses() {
+        let stored: Recalibration = serde_json::from_str(
+            r#"{"method":"platt","slope":0.8,"intercept":-0.1}"#
+        ).unwrap();
+        assert_eq!(stored, Recalibration::Platt { slope: 0.8, intercept: -0.1 });
+        assert_eq!(stored.method(), "platt");
+    }
+}
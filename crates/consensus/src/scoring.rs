@@ -0,0 +1,151 @@
+//! Proper scoring rules beyond Brier, and skill scores.
+//!
+//! - Log score: `ln(p)` of the probability given to what happened. Higher is
+//!   better, at most 0; a coin flip scores `ln(0.5) ≈ -0.693`.
+//! - Spherical score: `p_outcome / sqrt(p² + (1 - p)²)`. Higher is better,
+//!   between 0 and 1; a coin flip scores `0.707`.
+//! - Brier skill score: `1 - brier / reference_brier`. 0 means no better than
+//!   the reference, 1 is perfect, negative is worse than the reference. The
+//!   reference is either always saying 50% (Brier 0.25) or always saying the
+//!   base rate (climatology).
+
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::brier;
+
+/// Forecasts are clamped to `[EPSILON, 1 - EPSILON]` for the log score, so a
+/// confident miss costs `ln(1e-4) ≈ -9.2` instead of minus infinity.
+pub const LOG_SCORE_EPSILON: f64 = 1e-4;
+
+/// Brier score of always forecasting 50%.
+pub const UNIFORM_BRIER: f64 = 0.25;
+
+/// Log score of a single prediction.
+pub fn log_score_single(predicted: f64, actual: f64) -> f64 {
+    let p = predicted.clamp(LOG_SCORE_EPSILON, 1.0 - LOG_SCORE_EPSILON);
+    actual * p.ln() + (1.0 - actual) * (1.0 - p).ln()
+}
+
+/// Spherical score of a single prediction.
+pub fn spherical_score_single(predicted: f64, actual: f64) -> f64 {
+    let p = predicted.clamp(0.0, 1.0);
+    let norm = (p * p + (1.0 - p) * (1.0 - p)).sqrt();
+    (actual * p + (1.0 - actual) * (1.0 - p)) / norm
+}
+
+/// Mean log score, or `None` without predictions.
+pub fn log_score_average(predictions: &[(f64, f64)]) -> Option<f64> {
+    mean(predictions, log_score_single)
+}
+
+/// Mean spherical score, or `None` without predictions.
+pub fn spherical_score_average(predictions: &[(f64, f64)]) -> Option<f64> {
+    mean(predictions, spherical_score_single)
+}
+
+/// Reference forecast for skill scores.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillBaseline {
+    /// Always 50%.
+    #[default]
+    Uniform,
+    /// Always the observed base rate of the scored markets.
+    Climatology,
+}
+
+/// Brier skill score of a set of predictions against the baseline. `None`
+/// without predictions, or for climatology when every market resolved the
+/// same way (the reference is then perfect).
+pub fn brier_skill_score(predictions: &[(f64, f64)], baseline: SkillBaseline) -> Option<f64> {
+    let brier = brier::brier_score_average(predictions)?;
+    let reference = match baseline {
+        SkillBaseline::Uniform => UNIFORM_BRIER,
+        SkillBaseline::Climatology => {
+            let base_rate = predictions.iter().map(|(_, y)| y).sum::<f64>() / predictions.len() as f64;
+            base_rate * (1.0 - base_rate)
+        }
+    };
+    (reference > 0.0).then(|| 1.0 - brier / reference)
+}
+
+/// All per-prediction scores, as stored alongside a prediction.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PredictionScores {
+    pub brier: f64,
+    pub log: f64,
+    pub spherical: f64,
+}
+
+impl PredictionScores {
+    pub fn new(predicted: f64, actual: f64) -> Self {
+        Self {
+            brier: brier::brier_score_single(predicted, actual),
+            log: log_score_single(predicted, actual),
+            spherical: spherical_score_single(predicted, actual),
+        }
+    }
+
+    /// Scores of a stored prediction, as `(brier, log, spherical)` decimals.
+    pub fn decimal(predicted: &BigDecimal, actual: &BigDecimal) -> Result<(BigDecimal, BigDecimal, BigDecimal)> {
+        let scores = Self::new(predicted.to_string().parse()?, actual.to_string().parse()?);
+        Ok((
+            BigDecimal::from_str(&format!("{:.6}", scores.brier))?,
+            BigDecimal::from_str(&format!("{:.6}", scores.log))?,
+            BigDecimal::from_str(&format!("{:.6}", scores.spherical))?,
+        ))
+    }
+}
+
+fn mean(predictions: &[(f64, f64)], score: fn(f64, f64) -> f64) -> Option<f64> {
+    if predictions.is_empty() {
+        return None;
+    }
+    Some(predictions.iter().map(|(p, y)| score(*p, *y)).sum::<f64>() / predictions.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coin_flip_has_no_skill() {
+        let predictions = [(0.5, 1.0), (0.5, 0.0), (0.5, 1.0)];
+        assert!(brier_skill_score(&predictions, SkillBaseline::Uniform).unwrap().abs() < 1e-10);
+        assert!((log_score_average(&predictions).unwrap() - 0.5f64.ln()).abs() < 1e-10);
+        assert!((spherical_score_average(&predictions).unwrap() - 0.5f64.sqrt()).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_single_scores() {
+        assert!((log_score_single(0.8, 1.0) - 0.8f64.ln()).abs() < 1e-10);
+        assert!((log_score_single(0.8, 0.0) - 0.2f64.ln()).abs() < 1e-10);
+        // Confident misses are clamped rather than infinite
+        assert!(log_score_single(1.0, 0.0).is_finite());
+        assert!((spherical_score_single(1.0, 1.0) - 1.0).abs() < 1e-10);
+        assert!((spherical_score_single(0.8, 1.0) - 0.8 / 0.68f64.sqrt()).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_climatology_skill() {
+        // Base rate 0.75: always saying 75% has zero climatology skill but beats 50%
+        let predictions = [(0.75, 1.0), (0.75, 1.0), (0.75, 1.0), (0.75, 0.0)];
+        assert!(brier_skill_score(&predictions, SkillBaseline::Climatology).unwrap().abs() < 1e-10);
+        assert!(brier_skill_score(&predictions, SkillBaseline::Uniform).unwrap() > 0.0);
+        assert!(brier_skill_score(&[(0.9, 1.0)], SkillBaseline::Climatology).is_none());
+    }
+
+    #[test]
+    fn test_decimal() {
+        let (brier, log, spherical) = PredictionScores::decimal(
+            &BigDecimal::from_str("0.7").unwrap(),
+            &BigDecimal::from_str("1").unwrap(),
+        ).unwrap();
+        assert_eq!(brier, BigDecimal::from_str("0.090000").unwrap());
+        assert_eq!(log, BigDecimal::from_str(&format!("{:.6}", 0.7f64.ln())).unwrap());
+        assert!(spherical > BigDecimal::from_str("0.9").unwrap());
+    }
+}
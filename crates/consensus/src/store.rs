@@ -0,0 +1,298 @@
+//! Loading consensus inputs from the database.
+//!
+//! Shared by the consensus worker and the API, so a market's consensus is
+//! built from the same sources, accuracy records, fitted corrections and config
+//! wherever it is computed.
+
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::collections::{BTreeMap, HashMap};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::engine::{self, AccuracyStats, ConsensusConfig, SourceInput};
+use crate::recalibration::Recalibration;
+
+/// Parameters fitted on resolved markets. Load once and reuse across markets.
+#[derive(Debug, Clone, Default)]
+pub struct FittedParams {
+    pub extremizing_exponent: Option<f64>,
+    /// Learned weights by category (`None` = all categories), source slug -> weight.
+    pub weight_sets: HashMap<Option<Uuid>, HashMap<String, f64>>,
+    /// Recalibration curves by source slug and category (`None` = all categories).
+    pub recalibrations: HashMap<(String, Option<Uuid>), Recalibration>,
+}
+
+impl FittedParams {
+    pub async fn load(pool: &PgPool) -> Result<Self> {
+        Ok(Self {
+            extremizing_exponent: latest_extremizing_exponent(pool).await?,
+            weight_sets: latest_weight_sets(pool).await?,
+            recalibrations: recalibrations(pool).await?,
+        })
+    }
+}
+
+/// The market's config, with the fitted extremizing exponent filled in when
+/// none is set, and its sources as consensus inputs. No sources is not an
+/// error; the inputs are then empty.
+pub async fn load_market(
+    pool: &PgPool,
+    market_id: Uuid,
+    fitted: &FittedParams,
+) -> Result<(Vec<SourceInput>, ConsensusConfig)> {
+    let mut config = load_consensus_config(pool, market_id).await?;
+    if config.extremizing_exponent.is_none() {
+        config.extremizing_exponent = fitted.extremizing_exponent;
+    }
+    let inputs = load_market_inputs(pool, market_id, &config, fitted).await?;
+    Ok((inputs, config))
+}
+
+/// Every source quoting the market, with the accuracy it is weighted by.
+pub async fn load_market_inputs(
+    pool: &PgPool,
+    market_id: Uuid,
+    config: &ConsensusConfig,
+    fitted: &FittedParams,
+) -> Result<Vec<SourceInput>> {
+    #[derive(sqlx::FromRow)]
+    struct SourceData {
+        source_slug: String,
+        source_name: String,
+        play_money: bool,
+        category_id: Option<Uuid>,
+        probability: BigDecimal,
+        volume: Option<BigDecimal>,
+        liquidity: Option<BigDecimal>,
+        spread: Option<BigDecimal>,
+        forecaster_count: Option<i64>,
+        category_accuracy_pct: Option<BigDecimal>,
+        category_resolved: Option<i32>,
+        overall_accuracy_pct: Option<BigDecimal>,
+        overall_resolved: Option<i32>,
+        discovery_share: Option<f64>,
+        last_updated: DateTime<Utc>,
+    }
+
+    // One row per source, with volume and liquidity in USD (NULL for
+    // currencies without a rate): the record for the market's category (if any) and
+    // the source's overall record (NULL category), all time or over the
+    // configured rolling window, and its price discovery share against the
+    // other venues quoting the market, in the category if analysed.
+    let sources = sqlx::query_as::<_, SourceData>(
+        r#"
+        SELECT
+            s.slug as source_slug,
+            s.name as source_name,
+            s.play_money,
+            m.category_id,
+            sm.current_probability as probability,
+            to_usd(sm.volume, sm.currency) as volume,
+            to_usd(sm.liquidity, sm.currency) as liquidity,
+            sm.spread,
+            (sm.metadata->>'forecasters')::bigint as forecaster_count,
+            cat.accuracy_pct as category_accuracy_pct,
+            cat.total_resolved as category_resolved,
+            ovr.accuracy_pct as overall_accuracy_pct,
+            ovr.total_resolved as overall_resolved,
+            COALESCE(
+                (SELECT SUM(CASE WHEN ll.source_a_id = s.id THEN ll.a_share ELSE 1 - ll.a_share END * ll.observations)
+                     / NULLIF(SUM(ll.observations), 0)
+                 FROM lead_lag_stats ll
+                 WHERE ll.category_id = m.category_id
+                 AND (ll.source_a_id = s.id AND ll.source_b_id IN (SELECT source_id FROM source_markets WHERE market_id = $1)
+                   OR ll.source_b_id = s.id AND ll.source_a_id IN (SELECT source_id FROM source_markets WHERE market_id = $1))),
+                (SELECT SUM(CASE WHEN ll.source_a_id = s.id THEN ll.a_share ELSE 1 - ll.a_share END * ll.observations)
+                     / NULLIF(SUM(ll.observations), 0)
+                 FROM lead_lag_stats ll
+                 WHERE ll.category_id IS NULL
+                 AND (ll.source_a_id = s.id AND ll.source_b_id IN (SELECT source_id FROM source_markets WHERE market_id = $1)
+                   OR ll.source_b_id = s.id AND ll.source_a_id IN (SELECT source_id FROM source_markets WHERE market_id = $1)))
+            )::float8 as discovery_share,
+            COALESCE(sm.probability_changed_at, sm.updated_at) as last_updated
+        FROM source_markets sm
+        JOIN markets m ON sm.market_id = m.id
+        JOIN sources s ON sm.source_id = s.id
+        LEFT JOIN accuracy_records cat
+            ON cat.source_id = s.id AND cat.category_id = m.category_id
+            AND cat.horizon = 'final' AND cat.window_days IS NOT DISTINCT FROM $2
+        LEFT JOIN accuracy_records ovr
+            ON ovr.source_id = s.id AND ovr.category_id IS NULL
+            AND ovr.horizon = 'final' AND ovr.window_days IS NOT DISTINCT FROM $2
+        WHERE sm.market_id = $1
+        AND sm.current_probability IS NOT NULL
+        "#
+    )
+    .bind(market_id)
+    .bind(config.accuracy_window_days)
+    .fetch_all(pool)
+    .await?;
+
+    let Some(first) = sources.first() else {
+        return Ok(Vec::new());
+    };
+
+    // Prefer the category's learned weights, then the all-categories set
+    let learned = first.category_id
+        .and_then(|c| fitted.weight_sets.get(&Some(c)))
+        .or_else(|| fitted.weight_sets.get(&None));
+    let dependence = load_dependence(pool, market_id, first.category_id).await?;
+
+    Ok(sources.iter().map(|s| {
+        let category = accuracy_stats(&s.category_accuracy_pct, s.category_resolved);
+        let overall = accuracy_stats(&s.overall_accuracy_pct, s.overall_resolved);
+        let (accuracy_pct, resolved_count, accuracy_basis) = engine::category_accuracy(category, overall);
+
+        SourceInput {
+            source_id: s.source_slug.clone(),
+            source_name: s.source_name.clone(),
+            probability: s.probability.to_string().parse().unwrap_or(0.5),
+            accuracy_pct,
+            resolved_count,
+            volume: s.volume.as_ref().and_then(|v| v.to_string().parse().ok()),
+            play_money: s.play_money,
+            accuracy_basis,
+            last_updated: Some(s.last_updated),
+            liquidity: s.liquidity.as_ref().and_then(|v| v.to_string().parse().ok()),
+            spread: s.spread.as_ref().and_then(|v| v.to_string().parse().ok()),
+            forecaster_count: s.forecaster_count,
+            learned_weight: learned.and_then(|w| w.get(&s.source_slug).copied()),
+            discovery_share: s.discovery_share,
+            dependence: dependence.get(&s.source_slug).cloned().unwrap_or_default(),
+            // Prefer the source's curve in the category, then its overall curve
+            recalibration: s.category_id
+                .and_then(|c| fitted.recalibrations.get(&(s.source_slug.clone(), Some(c))))
+                .or_else(|| fitted.recalibrations.get(&(s.source_slug.clone(), None)))
+                .cloned(),
+        }
+    }).collect())
+}
+
+/// Pairwise dependence between the sources quoting the market, by source
+/// slug both ways. Category estimates replace overall ones.
+async fn load_dependence(
+    pool: &PgPool,
+    market_id: Uuid,
+    category_id: Option<Uuid>,
+) -> Result<HashMap<String, BTreeMap<String, f64>>> {
+    let rows: Vec<(String, String, BigDecimal)> = sqlx::query_as(
+        r#"
+        SELECT sa.slug, sb.slug, d.dependence
+        FROM source_dependence d
+        JOIN sources sa ON d.source_a_id = sa.id
+        JOIN sources sb ON d.source_b_id = sb.id
+        WHERE d.source_a_id IN (SELECT source_id FROM source_markets WHERE market_id = $1)
+        AND d.source_b_id IN (SELECT source_id FROM source_markets WHERE market_id = $1)
+        AND (d.category_id IS NULL OR d.category_id = $2)
+        ORDER BY d.category_id NULLS FIRST
+        "#
+    )
+    .bind(market_id)
+    .bind(category_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut dependence: HashMap<String, BTreeMap<String, f64>> = HashMap::new();
+    for (a, b, r) in rows {
+        let Ok(r) = r.to_string().parse::<f64>() else {
+            continue;
+        };
+        dependence.entry(a.clone()).or_default().insert(b.clone(), r);
+        dependence.entry(b).or_default().insert(a, r);
+    }
+    Ok(dependence)
+}
+
+/// Load the market's consensus configuration, falling back to the defaults
+/// when none is set, the stored JSON is invalid or the market is unknown.
+pub async fn load_consensus_config(pool: &PgPool, market_id: Uuid) -> Result<ConsensusConfig> {
+    let raw: Option<serde_json::Value> = sqlx::query_scalar::<_, Option<serde_json::Value>>(
+        "SELECT consensus_config FROM markets WHERE id = $1"
+    )
+    .bind(market_id)
+    .fetch_optional(pool)
+    .await?
+    .flatten();
+
+    Ok(match raw {
+        Some(value) => serde_json::from_value(value).unwrap_or_else(|e| {
+            warn!("Invalid consensus config for market {}: {}", market_id, e);
+            ConsensusConfig::default()
+        }),
+        None => ConsensusConfig::default(),
+    })
+}
+
+/// The most recently fitted extremizing exponent, if any.
+async fn latest_extremizing_exponent(pool: &PgPool) -> Result<Option<f64>> {
+    let exponent: Option<BigDecimal> = sqlx::query_scalar(
+        "SELECT exponent FROM extremizing_fits ORDER BY fitted_at DESC LIMIT 1"
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(exponent.and_then(|e| e.to_string().parse().ok()))
+}
+
+/// The latest learned weight sets that beat equal weights out of sample.
+async fn latest_weight_sets(pool: &PgPool) -> Result<HashMap<Option<Uuid>, HashMap<String, f64>>> {
+    let rows: Vec<(Option<Uuid>, serde_json::Value)> = sqlx::query_as(
+        r#"
+        SELECT category_id, weights
+        FROM source_weight_sets
+        WHERE version = (SELECT MAX(version) FROM source_weight_sets)
+        AND cv_loss < baseline_loss
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter()
+        .filter_map(|(category_id, weights)| {
+            match serde_json::from_value(weights) {
+                Ok(weights) => Some((category_id, weights)),
+                Err(e) => {
+                    warn!("Invalid learned weight set for category {:?}: {}", category_id, e);
+                    None
+                }
+            }
+        })
+        .collect())
+}
+
+/// Recalibration curves that beat the raw quotes out of sample.
+async fn recalibrations(pool: &PgPool) -> Result<HashMap<(String, Option<Uuid>), Recalibration>> {
+    let rows: Vec<(String, Option<Uuid>, serde_json::Value)> = sqlx::query_as(
+        r#"
+        SELECT s.slug, r.category_id, r.curve
+        FROM source_recalibrations r
+        JOIN sources s ON r.source_id = s.id
+        WHERE r.cv_loss < r.baseline_loss
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter()
+        .filter_map(|(slug, category_id, curve)| {
+            match serde_json::from_value(curve) {
+                Ok(curve) => Some(((slug, category_id), curve)),
+                Err(e) => {
+                    warn!("Invalid recalibration for {} in category {:?}: {}", slug, category_id, e);
+                    None
+                }
+            }
+        })
+        .collect())
+}
+
+fn accuracy_stats(accuracy_pct: &Option<BigDecimal>, resolved: Option<i32>) -> Option<AccuracyStats> {
+    let accuracy_pct = accuracy_pct.as_ref()?.to_string().parse().ok()?;
+    Some(AccuracyStats {
+        accuracy_pct,
+        resolved_count: resolved.unwrap_or(0),
+    })
+}
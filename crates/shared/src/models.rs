@@ -12,11 +12,24 @@ pub struct Source {
     pub name: String,
     pub source_type: String,
     pub api_base_url: Option<String>,
+    pub play_money: bool,
+    pub fee_model: Option<serde_json::Value>,
+    /// Currency code volume and liquidity are reported in.
+    pub currency: String,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Currency {
+    pub code: String,
+    pub name: String,
+    /// USD per unit. `None` leaves the currency out of USD figures.
+    pub usd_rate: Option<BigDecimal>,
+    pub updated_at: DateTime<Utc>,
+}
+
 // ─── Categories ───
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -41,6 +54,7 @@ pub struct Market {
     pub status: String,
     pub resolution_value: Option<BigDecimal>,
     pub resolution_date: Option<DateTime<Utc>>,
+    pub consensus_config: Option<serde_json::Value>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -56,8 +70,14 @@ pub struct SourceMarket {
     pub external_url: Option<String>,
     pub title: String,
     pub current_probability: Option<BigDecimal>,
+    pub probability_changed_at: Option<DateTime<Utc>>,
     pub volume: Option<BigDecimal>,
     pub liquidity: Option<BigDecimal>,
+    /// Currency code of `volume` and `liquidity`.
+    pub currency: Option<String>,
+    pub spread: Option<BigDecimal>,
+    pub best_bid: Option<BigDecimal>,
+    pub best_ask: Option<BigDecimal>,
     pub status: String,
     pub resolution_value: Option<BigDecimal>,
     pub resolution_date: Option<DateTime<Utc>>,
@@ -74,6 +94,8 @@ pub struct OddsHistory {
     pub source_market_id: Uuid,
     pub probability: BigDecimal,
     pub volume: Option<BigDecimal>,
+    /// Currency code of `volume`.
+    pub currency: Option<String>,
     pub trade_count: Option<i32>,
 }
 
@@ -84,10 +106,17 @@ pub struct AccuracyRecord {
     pub id: Uuid,
     pub source_id: Uuid,
     pub category_id: Option<Uuid>,
+    pub horizon: String,
+    /// Rolling window in days, `None` = all time.
+    pub window_days: Option<i32>,
     pub total_resolved: i32,
     pub correct_predictions: i32,
     pub brier_score: Option<BigDecimal>,
     pub accuracy_pct: Option<BigDecimal>,
+    pub log_score: Option<BigDecimal>,
+    pub spherical_score: Option<BigDecimal>,
+    pub brier_skill_score: Option<BigDecimal>,
+    pub climatology_skill_score: Option<BigDecimal>,
     pub last_calculated_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -100,6 +129,42 @@ pub struct PredictionScore {
     pub source_id: Uuid,
     pub market_id: Uuid,
     pub category_id: Option<Uuid>,
+    pub horizon: String,
+    pub predicted_probability: BigDecimal,
+    pub actual_outcome: BigDecimal,
+    pub brier_score: BigDecimal,
+    pub log_score: BigDecimal,
+    pub spherical_score: BigDecimal,
+    pub resolved_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+// ─── Users & Forecasts ───
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Team {
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct UserForecast {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub market_id: Uuid,
+    pub probability: BigDecimal,
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct UserPredictionScore {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub market_id: Uuid,
+    pub category_id: Option<Uuid>,
     pub predicted_probability: BigDecimal,
     pub actual_outcome: BigDecimal,
     pub brier_score: BigDecimal,
@@ -117,8 +182,15 @@ pub struct ConsensusSnapshot {
     pub confidence_score: Option<BigDecimal>,
     pub source_count: i32,
     pub agreement_score: Option<BigDecimal>,
+    pub interval_lower: Option<BigDecimal>,
+    pub interval_upper: Option<BigDecimal>,
     pub outlier_sources: serde_json::Value,
     pub weights: serde_json::Value,
+    pub aggregation_method: String,
+    pub aggregation_params: serde_json::Value,
+    pub extremized_probability: Option<BigDecimal>,
+    pub extremizing_exponent: Option<BigDecimal>,
+    pub stale_sources: serde_json::Value,
     pub created_at: DateTime<Utc>,
 }
 
@@ -138,6 +210,27 @@ pub struct MovementEvent {
     pub created_at: DateTime<Utc>,
 }
 
+// ─── Arbitrage ───
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ArbitrageOpportunity {
+    pub id: Uuid,
+    pub market_id: Uuid,
+    pub yes_source_market_id: Uuid,
+    pub no_source_market_id: Uuid,
+    pub yes_price: BigDecimal,
+    pub no_price: BigDecimal,
+    pub fees: BigDecimal,
+    pub edge: BigDecimal,
+    pub return_pct: BigDecimal,
+    pub max_edge: BigDecimal,
+    pub executable: bool,
+    pub play_money: bool,
+    pub detected_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
 // ─── Whale Tracking ───
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -186,7 +279,11 @@ pub struct SourceMarketSummary {
     pub source_name: String,
     pub source_slug: String,
     pub probability: Option<BigDecimal>,
+    /// Volume in the source's currency.
     pub volume: Option<BigDecimal>,
+    pub currency: Option<String>,
+    /// Volume in USD, `None` for currencies without a USD rate.
+    pub volume_usd: Option<BigDecimal>,
     pub accuracy_pct: Option<BigDecimal>,
     pub external_url: Option<String>,
 }
@@ -194,19 +291,87 @@ pub struct SourceMarketSummary {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ConsensusInfo {
     pub probability: BigDecimal,
+    pub extremized_probability: Option<BigDecimal>,
     pub confidence: Option<BigDecimal>,
+    pub interval_lower: Option<BigDecimal>,
+    pub interval_upper: Option<BigDecimal>,
     pub source_count: i32,
     pub agreement: Option<BigDecimal>,
 }
 
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct AccuracyLeaderboardEntry {
+    /// One plus the number of entries that are better beyond doubt. Entries
+    /// with the same count share a rank.
     pub rank: i64,
     pub source_name: String,
     pub source_slug: String,
     pub accuracy_pct: Option<BigDecimal>,
     pub brier_score: Option<BigDecimal>,
+    pub log_score: Option<BigDecimal>,
+    pub spherical_score: Option<BigDecimal>,
+    /// Against always forecasting 50%.
+    pub brier_skill_score: Option<BigDecimal>,
+    /// Against always forecasting the base rate.
+    pub climatology_skill_score: Option<BigDecimal>,
     pub total_resolved: i32,
+    /// The Forecise Consensus, scored as a pseudo-source.
+    pub is_consensus: bool,
+    /// The score the leaderboard is sorted by, with its standard error and
+    /// 95% confidence interval.
+    pub score: Option<f64>,
+    pub std_error: Option<f64>,
+    pub ci_lower: Option<f64>,
+    pub ci_upper: Option<f64>,
+    /// Whether another entry's interval overlaps this one.
+    pub tied: bool,
+}
+
+/// The consensus against its sources in one category (`None` = all categories).
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ConsensusComparisonEntry {
+    pub category_slug: Option<String>,
+    pub category_name: Option<String>,
+    pub markets: i64,
+    pub consensus_brier: Option<BigDecimal>,
+    pub extremized_brier: Option<BigDecimal>,
+    /// Mean Brier of all source predictions on the same markets.
+    pub mean_source_brier: Option<BigDecimal>,
+    pub best_source_slug: Option<String>,
+    pub best_source_brier: Option<BigDecimal>,
+    pub consensus_brier_1d: Option<BigDecimal>,
+    pub consensus_brier_7d: Option<BigDecimal>,
+    pub consensus_brier_30d: Option<BigDecimal>,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct UserLeaderboardEntry {
+    pub rank: i64,
+    pub user_id: Uuid,
+    pub user_name: Option<String>,
+    pub team_slug: Option<String>,
+    pub accuracy_pct: Option<BigDecimal>,
+    pub brier_score: Option<BigDecimal>,
+    /// Against always forecasting 50%.
+    pub brier_skill_score: Option<BigDecimal>,
+    /// Against always forecasting the base rate of the markets forecast.
+    pub climatology_skill_score: Option<BigDecimal>,
+    pub total_resolved: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TeamLeaderboardEntry {
+    pub rank: i64,
+    pub team_name: String,
+    pub team_slug: String,
+    pub member_count: i64,
+    pub accuracy_pct: Option<BigDecimal>,
+    pub brier_score: Option<BigDecimal>,
+    /// Against always forecasting 50%.
+    pub brier_skill_score: Option<BigDecimal>,
+    /// Against always forecasting the base rate of the markets forecast.
+    pub climatology_skill_score: Option<BigDecimal>,
+    pub total_resolved: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
@@ -219,6 +384,8 @@ pub struct MarketListItem {
     pub status: String,
     pub consensus_probability: Option<BigDecimal>,
     pub source_count: i64,
+    /// Volume across sources in USD, leaving out currencies without a USD rate.
+    pub volume_usd: Option<BigDecimal>,
     pub updated_at: DateTime<Utc>,
 }
 
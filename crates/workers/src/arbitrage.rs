@@ -0,0 +1,163 @@
+//! Arbitrage Detection
+//! Prices YES-on-one-venue plus NO-on-another for every linked market and
+//! keeps a row open for each pair with a positive edge after fees.
+
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use chrono::Utc;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::str::FromStr;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use forecise_consensus::arbitrage::{self, FeeModel, VenueQuote};
+
+/// Smallest edge, per unit of payout, worth recording.
+const MIN_EDGE: f64 = 0.005;
+/// Quotes older than this are left out; a stale price is not an opportunity.
+const MAX_QUOTE_AGE_MINUTES: i32 = 30;
+
+pub async fn run_arbitrage_detector(pool: PgPool) -> Result<()> {
+    // Wait for initial data ingestion
+    tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+
+    loop {
+        match detect_arbitrage(&pool).await {
+            Ok(count) => {
+                if count > 0 {
+                    info!("{} open arbitrage opportunities", count);
+                }
+            }
+            Err(e) => warn!("Arbitrage detection error: {}", e),
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(120)).await;
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct QuoteRow {
+    market_id: Uuid,
+    source_market_id: Uuid,
+    source_slug: String,
+    source_name: String,
+    probability: BigDecimal,
+    best_bid: Option<BigDecimal>,
+    best_ask: Option<BigDecimal>,
+    spread: Option<BigDecimal>,
+    fee_model: serde_json::Value,
+    play_money: bool,
+}
+
+/// Refresh open opportunities: record new ones, update those still there and
+/// close the rest. Returns the number open.
+pub async fn detect_arbitrage(pool: &PgPool) -> Result<usize> {
+    let sweep_start = Utc::now();
+
+    let rows = sqlx::query_as::<_, QuoteRow>(
+        r#"
+        SELECT
+            sm.market_id,
+            sm.id as source_market_id,
+            s.slug as source_slug,
+            s.name as source_name,
+            sm.current_probability as probability,
+            sm.best_bid,
+            sm.best_ask,
+            sm.spread,
+            s.fee_model,
+            s.play_money
+        FROM source_markets sm
+        JOIN markets m ON sm.market_id = m.id
+        JOIN sources s ON sm.source_id = s.id
+        WHERE m.status = 'active'
+        AND sm.status = 'active'
+        AND sm.current_probability IS NOT NULL
+        AND s.fee_model IS NOT NULL
+        AND sm.updated_at >= NOW() - make_interval(mins => $1)
+        ORDER BY sm.market_id
+        "#
+    )
+    .bind(MAX_QUOTE_AGE_MINUTES)
+    .fetch_all(pool)
+    .await?;
+
+    let mut by_market: HashMap<Uuid, Vec<(Uuid, VenueQuote)>> = HashMap::new();
+    for row in rows {
+        let fees: FeeModel = match serde_json::from_value(row.fee_model) {
+            Ok(fees) => fees,
+            Err(e) => {
+                warn!("Invalid fee model for source {}: {}", row.source_slug, e);
+                continue;
+            }
+        };
+        let parse = |v: &Option<BigDecimal>| v.as_ref().and_then(|v| v.to_string().parse().ok());
+        by_market.entry(row.market_id).or_default().push((row.source_market_id, VenueQuote {
+            source_id: row.source_slug,
+            source_name: row.source_name,
+            probability: row.probability.to_string().parse().unwrap_or(0.5),
+            best_bid: parse(&row.best_bid),
+            best_ask: parse(&row.best_ask),
+            spread: parse(&row.spread),
+            fees,
+            play_money: row.play_money,
+        }));
+    }
+
+    let mut count = 0;
+    for (market_id, venues) in &by_market {
+        if venues.len() < 2 {
+            continue;
+        }
+        let quotes: Vec<VenueQuote> = venues.iter().map(|(_, q)| q.clone()).collect();
+        for opportunity in arbitrage::find_opportunities(&quotes) {
+            if opportunity.edge < MIN_EDGE {
+                break;
+            }
+            let edge = BigDecimal::from_str(&format!("{:.6}", opportunity.edge))?;
+            sqlx::query(
+                r#"
+                INSERT INTO arbitrage_opportunities
+                    (market_id, yes_source_market_id, no_source_market_id, yes_price, no_price, fees,
+                     edge, return_pct, max_edge, executable, play_money, detected_at, last_seen_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $7, $9, $10, $11, $11)
+                ON CONFLICT (market_id, yes_source_market_id, no_source_market_id) WHERE closed_at IS NULL
+                DO UPDATE SET
+                    yes_price = EXCLUDED.yes_price,
+                    no_price = EXCLUDED.no_price,
+                    fees = EXCLUDED.fees,
+                    edge = EXCLUDED.edge,
+                    return_pct = EXCLUDED.return_pct,
+                    max_edge = GREATEST(arbitrage_opportunities.max_edge, EXCLUDED.edge),
+                    executable = EXCLUDED.executable,
+                    play_money = EXCLUDED.play_money,
+                    last_seen_at = EXCLUDED.last_seen_at
+                "#
+            )
+            .bind(market_id)
+            .bind(venues[opportunity.yes_venue].0)
+            .bind(venues[opportunity.no_venue].0)
+            .bind(BigDecimal::from_str(&format!("{:.6}", opportunity.yes_price))?)
+            .bind(BigDecimal::from_str(&format!("{:.6}", opportunity.no_price))?)
+            .bind(BigDecimal::from_str(&format!("{:.6}", opportunity.fees))?)
+            .bind(&edge)
+            .bind(BigDecimal::from_str(&format!("{:.4}", opportunity.return_pct))?)
+            .bind(opportunity.executable)
+            .bind(opportunity.play_money)
+            .bind(Utc::now())
+            .execute(pool)
+            .await?;
+            count += 1;
+        }
+    }
+
+    // Anything not seen this sweep has closed
+    sqlx::query(
+        "UPDATE arbitrage_opportunities SET closed_at = NOW() WHERE closed_at IS NULL AND last_seen_at < $1"
+    )
+    .bind(sweep_start)
+    .execute(pool)
+    .await?;
+
+    Ok(count)
+}
@@ -23,6 +23,7 @@ struct HighVolumeMarket {
     market_id: String,
     title: String,
     probability: f64,
+    /// In USD.
     total_volume: f64,
     source_count: i64,
 }
@@ -183,6 +184,8 @@ async fn get_top_movers(pool: &PgPool, since: chrono::DateTime<Utc>) -> Result<V
     Ok(movers)
 }
 
+/// Active markets by USD volume across sources. Volume in currencies without
+/// a USD rate (play money) is left out.
 async fn get_high_volume_markets(pool: &PgPool) -> Result<Vec<HighVolumeMarket>> {
     #[derive(sqlx::FromRow)]
     struct Row {
@@ -199,13 +202,12 @@ async fn get_high_volume_markets(pool: &PgPool) -> Result<Vec<HighVolumeMarket>>
             m.id as market_id,
             m.title,
             AVG(sm.current_probability) as avg_prob,
-            SUM(sm.volume) as total_volume,
+            SUM(to_usd(sm.volume, sm.currency)) as total_volume,
             COUNT(sm.id) as source_count
         FROM markets m
         JOIN source_markets sm ON sm.market_id = m.id
         WHERE m.status = 'active'
-        AND sm.volume IS NOT NULL
-        AND sm.volume > 0
+        AND to_usd(sm.volume, sm.currency) > 0
         GROUP BY m.id, m.title
         ORDER BY total_volume DESC
         LIMIT 10
@@ -359,14 +361,14 @@ fn generate_summary_text(
         ));
     }
 
-    if let Some(vol) = high_volume.first() {
-        if vol.total_volume > 0.0 {
-            lines.push(format!(
-                "Highest volume: \"{}\" with ${:.0} traded.",
-                truncate_title(&vol.title, 60),
-                vol.total_volume,
-            ));
-        }
+    if let Some(vol) = high_volume.first()
+        && vol.total_volume > 0.0
+    {
+        lines.push(format!(
+            "Highest volume: \"{}\" with ${:.0} traded.",
+            truncate_title(&vol.title, 60),
+            vol.total_volume,
+        ));
     }
 
     if stats.markets_with_consensus > 0 {
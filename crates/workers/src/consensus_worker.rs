@@ -1,30 +1,139 @@
 use anyhow::Result;
 use bigdecimal::BigDecimal;
-use chrono::Utc;
+use chrono::{DateTime, Duration, Utc};
 use sqlx::PgPool;
+use sqlx::postgres::PgListener;
+use std::collections::HashSet;
 use std::str::FromStr;
 use uuid::Uuid;
 use tracing::{info, warn};
 
-use forecise_consensus::engine::{self, SourceInput};
+use forecise_consensus::engine::{self, ConsensusResult};
+use forecise_consensus::store::{self, FittedParams};
+
+use crate::ingestion::MARKET_CHANGED_CHANNEL;
+
+/// Full recompute of every active market, as a safety net for missed
+/// notifications and for weights that drift with time (staleness).
+const FULL_SWEEP_SECS: u64 = 900;
+/// How long to collect change notifications before recomputing, so a burst
+/// of source updates for one market costs one recompute.
+const DEBOUNCE_SECS: u64 = 5;
+/// Smallest move in the consensus (or its extremized or interval bounds), in
+/// probability, that is worth a new snapshot.
+const MIN_SNAPSHOT_CHANGE: f64 = 0.001;
+/// An unchanged consensus is still snapshotted this often, so history and
+/// "has a recent consensus" checks stay current.
+const MAX_SNAPSHOT_AGE_HOURS: i64 = 6;
 
 pub async fn run_consensus_worker(pool: PgPool) -> Result<()> {
     // Wait for initial data
     tokio::time::sleep(std::time::Duration::from_secs(90)).await;
 
+    let mut listener = match PgListener::connect_with(&pool).await {
+        Ok(mut listener) => match listener.listen(MARKET_CHANGED_CHANNEL).await {
+            Ok(()) => Some(listener),
+            Err(e) => {
+                warn!("Could not listen for market changes, sweeping only: {}", e);
+                None
+            }
+        },
+        Err(e) => {
+            warn!("Could not listen for market changes, sweeping only: {}", e);
+            None
+        }
+    };
+
+    let mut sweep = tokio::time::interval(std::time::Duration::from_secs(FULL_SWEEP_SECS));
+    let mut pending: HashSet<Uuid> = HashSet::new();
+
     loop {
-        match compute_all_consensus(&pool).await {
-            Ok(count) => {
-                if count > 0 {
-                    info!("Computed consensus for {} markets", count);
+        tokio::select! {
+            _ = sweep.tick() => {
+                pending.clear();
+                match compute_all_consensus(&pool).await {
+                    Ok(count) => {
+                        if count > 0 {
+                            info!("Wrote consensus snapshots for {} markets", count);
+                        }
+                    }
+                    Err(e) => warn!("Consensus computation error: {}", e),
                 }
             }
-            Err(e) => warn!("Consensus computation error: {}", e),
+            notification = recv(&mut listener) => {
+                match notification {
+                    Ok(market_id) => {
+                        pending.extend(market_id);
+                        // Debounce: gather what else arrives, then recompute once
+                        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(DEBOUNCE_SECS);
+                        while let Ok(Ok(market_id)) = tokio::time::timeout_at(deadline, recv(&mut listener)).await {
+                            pending.extend(market_id);
+                        }
+                        let markets: Vec<Uuid> = pending.drain().collect();
+                        match compute_consensus_for(&pool, &markets).await {
+                            Ok(count) => {
+                                if count > 0 {
+                                    info!("Wrote consensus snapshots for {} of {} changed markets", count, markets.len());
+                                }
+                            }
+                            Err(e) => warn!("Consensus computation error: {}", e),
+                        }
+                    }
+                    Err(e) => {
+                        // The listener reconnects on the next receive; the sweep covers the gap
+                        warn!("Market change listener error: {}", e);
+                        tokio::time::sleep(std::time::Duration::from_secs(DEBOUNCE_SECS)).await;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The market id of the next change notification. Pends forever without a
+/// listener, leaving the sweep to do the work.
+async fn recv(listener: &mut Option<PgListener>) -> Result<Option<Uuid>> {
+    let Some(listener) = listener else {
+        return std::future::pending().await;
+    };
+    let notification = listener.recv().await?;
+    match Uuid::parse_str(notification.payload()) {
+        Ok(market_id) => Ok(Some(market_id)),
+        Err(_) => {
+            warn!("Ignoring market change notification {:?}", notification.payload());
+            Ok(None)
+        }
+    }
+}
+
+/// Recompute the given markets, skipping those no longer active.
+async fn compute_consensus_for(pool: &PgPool, market_ids: &[Uuid]) -> Result<usize> {
+    let active: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT id FROM markets WHERE id = ANY($1) AND status = 'active'"
+    )
+    .bind(market_ids)
+    .fetch_all(pool)
+    .await?;
+    if active.is_empty() {
+        return Ok(0);
+    }
+
+    let fitted = FittedParams::load(pool).await?;
+
+    let mut count = 0;
+    for market_id in active {
+        match compute_market_consensus(pool, market_id, &fitted).await {
+            Ok(true) => count += 1,
+            Ok(false) => {}
+            Err(e) => warn!("Failed consensus for market {}: {}", market_id, e),
         }
-        tokio::time::sleep(std::time::Duration::from_secs(300)).await;
     }
+
+    Ok(count)
 }
 
+/// Recompute every active market with sources. Returns the number of
+/// snapshots written.
 async fn compute_all_consensus(pool: &PgPool) -> Result<usize> {
     let market_ids: Vec<Uuid> = sqlx::query_scalar(
         r#"
@@ -40,77 +149,53 @@ async fn compute_all_consensus(pool: &PgPool) -> Result<usize> {
     .fetch_all(pool)
     .await?;
 
+    let fitted = FittedParams::load(pool).await?;
+
     let mut count = 0;
     for market_id in market_ids {
-        if let Err(e) = compute_market_consensus(pool, market_id).await {
-            warn!("Failed consensus for market {}: {}", market_id, e);
-        } else {
-            count += 1;
+        match compute_market_consensus(pool, market_id, &fitted).await {
+            Ok(true) => count += 1,
+            Ok(false) => {}
+            Err(e) => warn!("Failed consensus for market {}: {}", market_id, e),
         }
     }
 
     Ok(count)
 }
 
-async fn compute_market_consensus(pool: &PgPool, market_id: Uuid) -> Result<()> {
-    #[derive(sqlx::FromRow)]
-    struct SourceData {
-        source_slug: String,
-        source_name: String,
-        probability: BigDecimal,
-        volume: Option<BigDecimal>,
-        accuracy_pct: Option<BigDecimal>,
-        total_resolved: Option<i32>,
+/// Recompute a market's consensus and snapshot it if it changed meaningfully
+/// since the last snapshot. Returns whether a snapshot was written.
+async fn compute_market_consensus(pool: &PgPool, market_id: Uuid, fitted: &FittedParams) -> Result<bool> {
+    let (inputs, config) = store::load_market(pool, market_id, fitted).await?;
+    if inputs.is_empty() {
+        return Ok(false);
     }
+    let result = engine::calculate_consensus_with(&inputs, &config)?;
 
-    let sources = sqlx::query_as::<_, SourceData>(
-        r#"
-        SELECT
-            s.slug as source_slug,
-            s.name as source_name,
-            sm.current_probability as probability,
-            sm.volume,
-            ar.accuracy_pct,
-            ar.total_resolved
-        FROM source_markets sm
-        JOIN sources s ON sm.source_id = s.id
-        LEFT JOIN accuracy_records ar ON ar.source_id = s.id
-        WHERE sm.market_id = $1
-        AND sm.current_probability IS NOT NULL
-        "#
-    )
-    .bind(market_id)
-    .fetch_all(pool)
-    .await?;
-
-    if sources.is_empty() {
-        return Ok(());
+    if let Some(previous) = latest_snapshot(pool, market_id).await?
+        && !previous.differs_from(&result, &config.aggregation.params())
+    {
+        return Ok(false);
     }
 
-    let inputs: Vec<SourceInput> = sources.iter().map(|s| {
-        SourceInput {
-            source_id: s.source_slug.clone(),
-            source_name: s.source_name.clone(),
-            probability: s.probability.to_string().parse().unwrap_or(0.5),
-            accuracy_pct: s.accuracy_pct.as_ref().and_then(|a| a.to_string().parse().ok()),
-            resolved_count: s.total_resolved.unwrap_or(0),
-            volume: s.volume.as_ref().and_then(|v| v.to_string().parse().ok()),
-        }
-    }).collect();
-
-    let result = engine::calculate_consensus(&inputs)?;
-
     let prob = BigDecimal::from_str(&format!("{:.6}", result.probability))?;
     let confidence = BigDecimal::from_str(&format!("{:.4}", result.confidence))?;
     let agreement = BigDecimal::from_str(&format!("{:.4}", result.agreement))?;
+    let interval_lower = BigDecimal::from_str(&format!("{:.6}", result.interval_lower))?;
+    let interval_upper = BigDecimal::from_str(&format!("{:.6}", result.interval_upper))?;
+    let extremized = BigDecimal::from_str(&format!("{:.6}", result.extremized_probability))?;
+    let exponent = BigDecimal::from_str(&format!("{:.4}", result.extremizing_exponent))?;
     let weights_json = serde_json::to_value(&result.weights)?;
     let outliers_json = serde_json::to_value(&result.outliers)?;
+    let stale_json = serde_json::to_value(&result.stale_sources)?;
 
     sqlx::query(
         r#"
         INSERT INTO consensus_snapshots
-            (time, market_id, consensus_probability, confidence_score, source_count, agreement_score, weights, outlier_sources)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            (time, market_id, consensus_probability, confidence_score, source_count, agreement_score, weights, outlier_sources,
+             aggregation_method, aggregation_params, extremized_probability, extremizing_exponent, stale_sources,
+             interval_lower, interval_upper)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
         "#
     )
     .bind(Utc::now())
@@ -121,8 +206,79 @@ async fn compute_market_consensus(pool: &PgPool, market_id: Uuid) -> Result<()>
     .bind(&agreement)
     .bind(&weights_json)
     .bind(&outliers_json)
+    .bind(&result.method)
+    .bind(config.aggregation.params())
+    .bind(&extremized)
+    .bind(&exponent)
+    .bind(&stale_json)
+    .bind(&interval_lower)
+    .bind(&interval_upper)
     .execute(pool)
     .await?;
 
-    Ok(())
+    Ok(true)
+}
+
+/// The parts of the latest snapshot a new consensus is compared against.
+#[derive(sqlx::FromRow)]
+struct LatestSnapshot {
+    time: DateTime<Utc>,
+    consensus_probability: BigDecimal,
+    extremized_probability: Option<BigDecimal>,
+    interval_lower: Option<BigDecimal>,
+    interval_upper: Option<BigDecimal>,
+    source_count: i32,
+    aggregation_method: String,
+    aggregation_params: serde_json::Value,
+    outlier_sources: Option<serde_json::Value>,
+    stale_sources: serde_json::Value,
+}
+
+impl LatestSnapshot {
+    /// Whether `result` is worth a new snapshot: a move of at least
+    /// [`MIN_SNAPSHOT_CHANGE`], a different source count, outliers, stale
+    /// sources or method, or the last snapshot is older than
+    /// [`MAX_SNAPSHOT_AGE_HOURS`].
+    fn differs_from(&self, result: &ConsensusResult, params: &serde_json::Value) -> bool {
+        let moved = |previous: Option<&BigDecimal>, current: f64| match previous.and_then(|p| p.to_string().parse::<f64>().ok()) {
+            Some(previous) => (previous - current).abs() >= MIN_SNAPSHOT_CHANGE,
+            None => true,
+        };
+        let outliers: HashSet<&str> = result.outliers.iter().map(|o| o.source_id.as_str()).collect();
+        let stale: HashSet<&str> = result.stale_sources.iter().map(|s| s.source_id.as_str()).collect();
+
+        Utc::now() - self.time >= Duration::hours(MAX_SNAPSHOT_AGE_HOURS)
+            || moved(Some(&self.consensus_probability), result.probability)
+            || moved(self.extremized_probability.as_ref(), result.extremized_probability)
+            || moved(self.interval_lower.as_ref(), result.interval_lower)
+            || moved(self.interval_upper.as_ref(), result.interval_upper)
+            || self.source_count != result.source_count as i32
+            || self.aggregation_method != result.method
+            || &self.aggregation_params != params
+            || self.outlier_sources.as_ref().map(source_ids).unwrap_or_default() != outliers
+            || source_ids(&self.stale_sources) != stale
+    }
+}
+
+/// Source ids in a stored list of outlier or stale sources.
+fn source_ids(sources: &serde_json::Value) -> HashSet<&str> {
+    sources.as_array()
+        .map(|sources| sources.iter().filter_map(|s| s["source_id"].as_str()).collect())
+        .unwrap_or_default()
+}
+
+async fn latest_snapshot(pool: &PgPool, market_id: Uuid) -> Result<Option<LatestSnapshot>> {
+    Ok(sqlx::query_as::<_, LatestSnapshot>(
+        r#"
+        SELECT time, consensus_probability, extremized_probability, interval_lower, interval_upper,
+               source_count, aggregation_method, aggregation_params, outlier_sources, stale_sources
+        FROM consensus_snapshots
+        WHERE market_id = $1
+        ORDER BY time DESC
+        LIMIT 1
+        "#
+    )
+    .bind(market_id)
+    .fetch_optional(pool)
+    .await?)
 }
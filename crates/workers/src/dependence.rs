@@ -0,0 +1,115 @@
+//! Source Dependence
+//! Estimates how much each pair of sources shares one opinion, from odds
+//! co-movement (the lead-lag peak correlation) and the correlation of their
+//! residuals on resolved markets both forecast.
+
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use chrono::Utc;
+use sqlx::PgPool;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use forecise_consensus::dependence::{self, RESIDUAL_HORIZON};
+
+#[derive(sqlx::FromRow)]
+struct QuoteRow {
+    market_id: Uuid,
+    category_id: Option<Uuid>,
+    source_id: Uuid,
+    predicted: f64,
+}
+
+#[derive(Default)]
+struct PairEstimate {
+    comovement: Option<f64>,
+    residual_correlation: Option<f64>,
+    shared_markets: i64,
+}
+
+/// Recompute every pair with co-movement or enough shared resolved markets.
+/// Run after the lead-lag analysis. Returns the number of rows written.
+pub async fn estimate_dependence(pool: &PgPool) -> Result<usize> {
+    let run_start = Utc::now();
+    // Keyed by (lower source id, higher source id, category)
+    let mut pairs: BTreeMap<(Uuid, Uuid, Option<Uuid>), PairEstimate> = BTreeMap::new();
+    let key = |a: Uuid, b: Uuid, category_id: Option<Uuid>| (a.min(b), a.max(b), category_id);
+
+    let comovement: Vec<(Uuid, Uuid, Option<Uuid>, f64)> = sqlx::query_as(
+        "SELECT source_a_id, source_b_id, category_id, best_correlation::float8 FROM lead_lag_stats"
+    )
+    .fetch_all(pool)
+    .await?;
+    for (a, b, category_id, r) in comovement {
+        pairs.entry(key(a, b, category_id)).or_default().comovement = Some(r);
+    }
+
+    // Residual correlation on shared resolved markets, per category and overall
+    let quotes = sqlx::query_as::<_, QuoteRow>(
+        r#"
+        SELECT market_id, category_id, source_id, predicted_probability::float8 as predicted
+        FROM prediction_scores
+        WHERE horizon = $1
+        ORDER BY market_id
+        "#
+    )
+    .bind(RESIDUAL_HORIZON)
+    .fetch_all(pool)
+    .await?;
+    // Markets without a category only count towards the overall figure
+    let mut markets: BTreeMap<Option<Uuid>, Vec<Vec<(Uuid, f64)>>> = BTreeMap::new();
+    for group in quotes.chunk_by(|a, b| a.market_id == b.market_id) {
+        let market: Vec<(Uuid, f64)> = group.iter().map(|q| (q.source_id, q.predicted)).collect();
+        if let Some(category_id) = group[0].category_id {
+            markets.entry(Some(category_id)).or_default().push(market.clone());
+        }
+        markets.entry(None).or_default().push(market);
+    }
+    for (category_id, markets) in &markets {
+        for ((a, b), (correlation, shared_markets)) in dependence::residual_correlations(markets) {
+            let estimate = pairs.entry(key(a, b, *category_id)).or_default();
+            estimate.residual_correlation = correlation;
+            estimate.shared_markets = shared_markets;
+        }
+    }
+
+    let mut count = 0;
+    for ((source_a, source_b, category_id), estimate) in &pairs {
+        let Some(dependence) = dependence::combine(estimate.comovement, estimate.residual_correlation) else {
+            continue;
+        };
+        let decimal = |v: Option<f64>| v.filter(|v| v.is_finite()).and_then(|v| BigDecimal::from_str(&format!("{:.6}", v)).ok());
+        sqlx::query(
+            r#"
+            INSERT INTO source_dependence
+                (source_a_id, source_b_id, category_id, comovement, residual_correlation, shared_markets, dependence, calculated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (source_a_id, source_b_id, category_id) DO UPDATE SET
+                comovement = EXCLUDED.comovement,
+                residual_correlation = EXCLUDED.residual_correlation,
+                shared_markets = EXCLUDED.shared_markets,
+                dependence = EXCLUDED.dependence,
+                calculated_at = EXCLUDED.calculated_at
+            "#
+        )
+        .bind(source_a)
+        .bind(source_b)
+        .bind(category_id)
+        .bind(decimal(estimate.comovement))
+        .bind(decimal(estimate.residual_correlation))
+        .bind(estimate.shared_markets as i32)
+        .bind(BigDecimal::from_str(&format!("{:.6}", dependence))?)
+        .bind(Utc::now())
+        .execute(pool)
+        .await?;
+        count += 1;
+    }
+
+    sqlx::query("DELETE FROM source_dependence WHERE calculated_at < $1")
+        .bind(run_start)
+        .execute(pool)
+        .await?;
+
+    Ok(count)
+}
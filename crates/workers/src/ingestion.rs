@@ -5,63 +5,115 @@ use sqlx::PgPool;
 use uuid::Uuid;
 use std::str::FromStr;
 
-/// Upsert a source market and record its odds
-pub async fn upsert_source_market(
-    pool: &PgPool,
-    source_slug: &str,
-    external_id: &str,
-    title: &str,
-    probability: f64,
-    volume: Option<f64>,
-    external_url: Option<&str>,
-    metadata: serde_json::Value,
-) -> Result<Uuid> {
-    let source_id: Uuid = sqlx::query_scalar(
-        "SELECT id FROM sources WHERE slug = $1"
+/// Postgres channel notified with a unified market's id when one of its
+/// source markets changes in a way that can move the consensus.
+pub const MARKET_CHANGED_CHANNEL: &str = "market_changed";
+
+/// A source market as fetched from a source's API.
+pub struct SourceMarketUpdate<'a> {
+    pub source_slug: &'a str,
+    pub external_id: &'a str,
+    pub title: &'a str,
+    pub probability: f64,
+    pub volume: Option<f64>,
+    pub liquidity: Option<f64>,
+    /// Bid-ask spread in probability points, for order book markets.
+    pub spread: Option<f64>,
+    /// Best YES bid and ask, for order book markets.
+    pub best_bid: Option<f64>,
+    pub best_ask: Option<f64>,
+    pub external_url: Option<&'a str>,
+    pub metadata: serde_json::Value,
+}
+
+/// Upsert a source market and record its odds, with volume and liquidity in
+/// the source's currency. Notifies [`MARKET_CHANGED_CHANNEL`] when a linked
+/// source market's probability or depth changed.
+pub async fn upsert_source_market(pool: &PgPool, market: SourceMarketUpdate<'_>) -> Result<Uuid> {
+    let (source_id, currency): (Uuid, String) = sqlx::query_as(
+        "SELECT id, currency FROM sources WHERE slug = $1"
     )
-    .bind(source_slug)
+    .bind(market.source_slug)
     .fetch_one(pool)
     .await?;
 
-    let prob = BigDecimal::from_str(&format!("{:.6}", probability))?;
-    let vol = volume.map(|v| BigDecimal::from_str(&format!("{:.2}", v)).unwrap_or_default());
+    let prob = BigDecimal::from_str(&format!("{:.6}", market.probability))?;
+    let vol = market.volume.map(|v| BigDecimal::from_str(&format!("{:.2}", v)).unwrap_or_default());
+    let liquidity = market.liquidity.map(|l| BigDecimal::from_str(&format!("{:.2}", l)).unwrap_or_default());
+    let spread = market.spread.map(|s| BigDecimal::from_str(&format!("{:.6}", s)).unwrap_or_default());
+    let best_bid = market.best_bid.map(|b| BigDecimal::from_str(&format!("{:.6}", b)).unwrap_or_default());
+    let best_ask = market.best_ask.map(|a| BigDecimal::from_str(&format!("{:.6}", a)).unwrap_or_default());
 
-    // Upsert source market
-    let source_market_id: Uuid = sqlx::query_scalar(
+    // Upsert source market, comparing against the row as it was before
+    let (source_market_id, market_id, changed): (Uuid, Option<Uuid>, Option<bool>) = sqlx::query_as(
         r#"
-        INSERT INTO source_markets (source_id, external_id, title, current_probability, volume, external_url, metadata)
-        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        WITH prev AS (
+            SELECT current_probability, volume, liquidity, spread
+            FROM source_markets
+            WHERE source_id = $1 AND external_id = $2
+        )
+        INSERT INTO source_markets
+            (source_id, external_id, title, current_probability, volume, liquidity, currency, spread, best_bid, best_ask, external_url, metadata)
+        VALUES ($1, $2, $3, $4, $5, $6, $12, $7, $8, $9, $10, $11)
         ON CONFLICT (source_id, external_id) DO UPDATE SET
             title = EXCLUDED.title,
             current_probability = EXCLUDED.current_probability,
+            probability_changed_at = CASE
+                WHEN source_markets.current_probability IS DISTINCT FROM EXCLUDED.current_probability THEN NOW()
+                ELSE source_markets.probability_changed_at
+            END,
             volume = EXCLUDED.volume,
+            liquidity = EXCLUDED.liquidity,
+            currency = EXCLUDED.currency,
+            spread = EXCLUDED.spread,
+            best_bid = EXCLUDED.best_bid,
+            best_ask = EXCLUDED.best_ask,
             external_url = EXCLUDED.external_url,
             metadata = EXCLUDED.metadata,
             updated_at = NOW()
-        RETURNING id
+        RETURNING id, market_id, (
+            SELECT current_probability IS DISTINCT FROM $4
+                OR volume IS DISTINCT FROM $5
+                OR liquidity IS DISTINCT FROM $6
+                OR spread IS DISTINCT FROM $7
+            FROM prev
+        )
         "#
     )
     .bind(source_id)
-    .bind(external_id)
-    .bind(title)
+    .bind(market.external_id)
+    .bind(market.title)
     .bind(&prob)
     .bind(&vol)
-    .bind(external_url)
-    .bind(&metadata)
+    .bind(&liquidity)
+    .bind(&spread)
+    .bind(&best_bid)
+    .bind(&best_ask)
+    .bind(market.external_url)
+    .bind(&market.metadata)
+    .bind(&currency)
     .fetch_one(pool)
     .await?;
 
+    // A new source market has no unified market yet; linking it notifies
+    if let Some(market_id) = market_id
+        && changed.unwrap_or(true)
+    {
+        notify_market_changed(pool, market_id).await?;
+    }
+
     // Record odds history
     sqlx::query(
         r#"
-        INSERT INTO odds_history (time, source_market_id, probability, volume)
-        VALUES ($1, $2, $3, $4)
+        INSERT INTO odds_history (time, source_market_id, probability, volume, currency)
+        VALUES ($1, $2, $3, $4, $5)
         "#
     )
     .bind(Utc::now())
     .bind(source_market_id)
     .bind(&prob)
     .bind(&vol)
+    .bind(&currency)
     .execute(pool)
     .await?;
 
@@ -118,6 +170,16 @@ pub async fn ensure_unified_market(
         .bind(source_market_id)
         .execute(pool)
         .await?;
+    notify_market_changed(pool, market_id).await?;
 
     Ok(market_id)
 }
+
+async fn notify_market_changed(pool: &PgPool, market_id: Uuid) -> Result<()> {
+    sqlx::query("SELECT pg_notify($1, $2)")
+        .bind(MARKET_CHANGED_CHANNEL)
+        .bind(market_id.to_string())
+        .execute(pool)
+        .await?;
+    Ok(())
+}
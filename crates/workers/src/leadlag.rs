@@ -0,0 +1,185 @@
+//! Lead-lag Analysis
+//! Finds which venue moves first on markets quoted by several, per source
+//! pair overall and per category, from recent odds history.
+
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::dependence;
+use forecise_consensus::leadlag::{self, DEFAULT_MAX_LAG, LeadLag};
+
+/// Odds history analysed, in days.
+const WINDOW_DAYS: i32 = 30;
+const RUN_INTERVAL_SECS: u64 = 6 * 3600;
+
+pub async fn run_lead_lag_worker(pool: PgPool) -> Result<()> {
+    // Wait for initial data ingestion
+    tokio::time::sleep(std::time::Duration::from_secs(180)).await;
+
+    loop {
+        match analyse_lead_lag(&pool).await {
+            Ok(count) => info!("Lead-lag: updated {} source pairs", count),
+            Err(e) => warn!("Lead-lag analysis error: {}", e),
+        }
+        // Co-movement feeds the dependence estimates
+        match dependence::estimate_dependence(&pool).await {
+            Ok(count) => info!("Dependence: updated {} source pairs", count),
+            Err(e) => warn!("Dependence estimation error: {}", e),
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(RUN_INTERVAL_SECS)).await;
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct OddsRow {
+    market_id: Uuid,
+    category_id: Option<Uuid>,
+    source_id: Uuid,
+    source_slug: String,
+    time: DateTime<Utc>,
+    probability: BigDecimal,
+}
+
+/// One venue's odds on one market.
+struct Series {
+    source_id: Uuid,
+    source_slug: String,
+    points: Vec<(DateTime<Utc>, f64)>,
+}
+
+/// Recompute every source pair. Returns the number of rows written.
+pub async fn analyse_lead_lag(pool: &PgPool) -> Result<usize> {
+    let run_start = Utc::now();
+
+    let rows = sqlx::query_as::<_, OddsRow>(
+        r#"
+        SELECT sm.market_id, m.category_id, s.id as source_id, s.slug as source_slug, oh.time, oh.probability
+        FROM odds_history oh
+        JOIN source_markets sm ON oh.source_market_id = sm.id
+        JOIN markets m ON sm.market_id = m.id
+        JOIN sources s ON sm.source_id = s.id
+        WHERE oh.time >= NOW() - make_interval(days => $1)
+        AND sm.market_id IN (
+            SELECT market_id FROM source_markets
+            WHERE market_id IS NOT NULL
+            GROUP BY market_id
+            HAVING COUNT(DISTINCT source_id) >= 2
+        )
+        ORDER BY sm.market_id, s.slug, oh.time
+        "#
+    )
+    .bind(WINDOW_DAYS)
+    .fetch_all(pool)
+    .await?;
+
+    // market -> (category, series per source, in slug order)
+    let mut markets: HashMap<Uuid, (Option<Uuid>, Vec<Series>)> = HashMap::new();
+    for row in rows {
+        let (_, series) = markets.entry(row.market_id).or_insert_with(|| (row.category_id, Vec::new()));
+        let probability = row.probability.to_string().parse().unwrap_or(0.5);
+        match series.last_mut() {
+            Some(last) if last.source_id == row.source_id => last.points.push((row.time, probability)),
+            _ => series.push(Series {
+                source_id: row.source_id,
+                source_slug: row.source_slug,
+                points: vec![(row.time, probability)],
+            }),
+        }
+    }
+
+    // One grid for every pair, so results pool by lag: at least as coarse as
+    // the slowest venue's median gap between polls
+    let mut cadences: HashMap<Uuid, Vec<Duration>> = HashMap::new();
+    for (_, series) in markets.values() {
+        for s in series {
+            if let Some(gap) = leadlag::median_gap(&s.points) {
+                cadences.entry(s.source_id).or_default().push(gap);
+            }
+        }
+    }
+    let step = leadlag::grid_step(cadences.into_values().map(|mut gaps| {
+        gaps.sort();
+        gaps[gaps.len() / 2]
+    }));
+    let step_minutes = step.num_minutes() as i32;
+
+    // (source_a, source_b, category) -> per-market results; category None = all
+    let mut results: BTreeMap<(Uuid, Uuid, Option<Uuid>), Vec<LeadLag>> = BTreeMap::new();
+    for (category_id, series) in markets.values() {
+        for (i, a) in series.iter().enumerate() {
+            for b in &series[i + 1..] {
+                if a.source_slug >= b.source_slug {
+                    continue;
+                }
+                let (Some(a_first), Some(b_first)) = (a.points.first(), b.points.first()) else {
+                    continue;
+                };
+                let start = a_first.0.max(b_first.0);
+                let end = a.points.last().map_or(start, |p| p.0).max(b.points.last().map_or(start, |p| p.0));
+                let a_grid = leadlag::resample(&a.points, start, end, step);
+                let b_grid = leadlag::resample(&b.points, start, end, step);
+                let Some(result) = leadlag::lead_lag(&a_grid, &b_grid, DEFAULT_MAX_LAG) else {
+                    continue;
+                };
+                if let Some(category_id) = category_id {
+                    results.entry((a.source_id, b.source_id, Some(*category_id))).or_default().push(result.clone());
+                }
+                results.entry((a.source_id, b.source_id, None)).or_default().push(result);
+            }
+        }
+    }
+
+    let mut count = 0;
+    for ((source_a, source_b, category_id), per_market) in &results {
+        let Some(pooled) = leadlag::pooled(per_market) else {
+            continue;
+        };
+        sqlx::query(
+            r#"
+            INSERT INTO lead_lag_stats
+                (source_a_id, source_b_id, category_id, markets, observations, window_days, step_minutes,
+                 best_lag_minutes, best_correlation, a_share, correlations, calculated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            ON CONFLICT (source_a_id, source_b_id, category_id) DO UPDATE SET
+                markets = EXCLUDED.markets,
+                observations = EXCLUDED.observations,
+                window_days = EXCLUDED.window_days,
+                step_minutes = EXCLUDED.step_minutes,
+                best_lag_minutes = EXCLUDED.best_lag_minutes,
+                best_correlation = EXCLUDED.best_correlation,
+                a_share = EXCLUDED.a_share,
+                correlations = EXCLUDED.correlations,
+                calculated_at = EXCLUDED.calculated_at
+            "#
+        )
+        .bind(source_a)
+        .bind(source_b)
+        .bind(category_id)
+        .bind(per_market.len() as i32)
+        .bind(pooled.observations as i32)
+        .bind(WINDOW_DAYS)
+        .bind(step_minutes)
+        .bind(pooled.best_lag * step_minutes)
+        .bind(BigDecimal::from_str(&format!("{:.6}", pooled.best_correlation))?)
+        .bind(BigDecimal::from_str(&format!("{:.6}", pooled.a_share))?)
+        .bind(serde_json::to_value(&pooled.correlations)?)
+        .bind(Utc::now())
+        .execute(pool)
+        .await?;
+        count += 1;
+    }
+
+    // Pairs with no overlapping history left in the window
+    sqlx::query("DELETE FROM lead_lag_stats WHERE calculated_at < $1")
+        .bind(run_start)
+        .execute(pool)
+        .await?;
+
+    Ok(count)
+}
@@ -3,10 +3,14 @@ use sqlx::postgres::PgPoolOptions;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 mod sources;
+mod arbitrage;
 mod ingestion;
 mod movement;
 mod consensus_worker;
 mod briefing;
+mod scoring;
+mod leadlag;
+mod dependence;
 
 use sources::{polymarket, metaculus, manifold};
 
@@ -38,6 +42,9 @@ async fn main() -> Result<()> {
     let pool4 = pool.clone();
     let pool5 = pool.clone();
     let pool6 = pool.clone();
+    let pool7 = pool.clone();
+    let pool8 = pool.clone();
+    let pool9 = pool.clone();
     let client1 = http_client.clone();
     let client2 = http_client.clone();
     let client3 = http_client.clone();
@@ -63,6 +70,15 @@ async fn main() -> Result<()> {
         r = briefing::run_briefing_generator(pool6) => {
             tracing::error!("Briefing generator exited: {:?}", r);
         }
+        r = scoring::run_scoring_worker(pool7) => {
+            tracing::error!("Scoring worker exited: {:?}", r);
+        }
+        r = arbitrage::run_arbitrage_detector(pool8) => {
+            tracing::error!("Arbitrage detector exited: {:?}", r);
+        }
+        r = leadlag::run_lead_lag_worker(pool9) => {
+            tracing::error!("Lead-lag worker exited: {:?}", r);
+        }
     }
 
     Ok(())
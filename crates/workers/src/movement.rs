@@ -50,36 +50,36 @@ pub async fn detect_movements(pool: &PgPool) -> Result<usize> {
 
         let change = (current - previous).abs();
 
-        if change >= MOVEMENT_THRESHOLD {
-            if let Some(market_id) = &market.market_id {
-                let change_pct = BigDecimal::from_str(&format!("{:.4}", change))?;
-                let prob_before = BigDecimal::from_str(&format!("{:.6}", previous))?;
-                let prob_after = BigDecimal::from_str(&format!("{:.6}", current))?;
+        if change >= MOVEMENT_THRESHOLD
+            && let Some(market_id) = &market.market_id
+        {
+            let change_pct = BigDecimal::from_str(&format!("{:.4}", change))?;
+            let prob_before = BigDecimal::from_str(&format!("{:.6}", previous))?;
+            let prob_after = BigDecimal::from_str(&format!("{:.6}", current))?;
 
-                sqlx::query(
-                    r#"
-                    INSERT INTO movement_events
-                        (source_market_id, market_id, probability_before, probability_after, change_pct, detected_at)
-                    VALUES ($1, $2, $3, $4, $5, $6)
-                    "#
-                )
-                .bind(market.source_market_id)
-                .bind(market_id)
-                .bind(&prob_before)
-                .bind(&prob_after)
-                .bind(&change_pct)
-                .bind(Utc::now())
-                .execute(pool)
-                .await?;
+            sqlx::query(
+                r#"
+                INSERT INTO movement_events
+                    (source_market_id, market_id, probability_before, probability_after, change_pct, detected_at)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                "#
+            )
+            .bind(market.source_market_id)
+            .bind(market_id)
+            .bind(&prob_before)
+            .bind(&prob_after)
+            .bind(&change_pct)
+            .bind(Utc::now())
+            .execute(pool)
+            .await?;
 
-                let direction = if current > previous { "UP" } else { "DOWN" };
-                info!(
-                    "Movement detected: {} {:.1}% -> {:.1}% ({} {:.1}%)",
-                    direction, previous * 100.0, current * 100.0, direction, change * 100.0
-                );
+            let direction = if current > previous { "UP" } else { "DOWN" };
+            info!(
+                "Movement detected: {} {:.1}% -> {:.1}% ({} {:.1}%)",
+                direction, previous * 100.0, current * 100.0, direction, change * 100.0
+            );
 
-                count += 1;
-            }
+            count += 1;
         }
     }
 
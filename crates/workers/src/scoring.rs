@@ -0,0 +1,731 @@
+//! Resolution Scoring
+//! Scores source and user forecasts on resolved markets with the Brier, log
+//! and spherical scores and refreshes the per-source accuracy records used for consensus weighting.
+
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use forecise_consensus::aggregator::{Aggregator, LogOddsPool};
+use forecise_consensus::brier;
+use forecise_consensus::engine::{ACCURACY_WINDOWS_DAYS, SourceWeight};
+use forecise_consensus::extremize;
+use forecise_consensus::learned::{self, FitObjective, LearnedWeights, WeightSample};
+use forecise_consensus::recalibration::{self, RecalibrationFit};
+use forecise_consensus::scoring::PredictionScores;
+
+const SCORING_INTERVAL_SECS: u64 = 900; // 15 minutes
+
+/// Horizons the consensus is scored at, with the minimum time before resolution.
+const CONSENSUS_HORIZONS: [(&str, &str); 5] = [
+    ("final", "0"),
+    ("1d", "1 day"),
+    ("7d", "7 days"),
+    ("30d", "30 days"),
+    ("90d", "90 days"),
+];
+
+/// When a source's forecast is taken for scoring.
+#[derive(Debug, Clone, Copy)]
+enum SourceHorizon {
+    /// Last probability before resolution.
+    Final,
+    /// Time-weighted mean probability over the market's life.
+    TimeAverage,
+    /// Last probability at least this long before resolution.
+    Before(&'static str, &'static str),
+}
+
+const SOURCE_HORIZONS: [SourceHorizon; 6] = [
+    SourceHorizon::Final,
+    SourceHorizon::TimeAverage,
+    SourceHorizon::Before("1d", "1 day"),
+    SourceHorizon::Before("7d", "7 days"),
+    SourceHorizon::Before("30d", "30 days"),
+    SourceHorizon::Before("90d", "90 days"),
+];
+
+impl SourceHorizon {
+    /// Value of `prediction_scores.horizon`.
+    fn label(&self) -> &'static str {
+        match self {
+            SourceHorizon::Final => "final",
+            SourceHorizon::TimeAverage => "time_avg",
+            SourceHorizon::Before(label, _) => label,
+        }
+    }
+
+    fn interval(&self) -> &'static str {
+        match self {
+            SourceHorizon::Before(_, interval) => interval,
+            _ => "0",
+        }
+    }
+
+    /// SQL for the scored probability of source market `sm` on market `m`.
+    /// `$2` is bound to [`Self::interval`].
+    fn predicted_sql(&self) -> &'static str {
+        match self {
+            SourceHorizon::Final => r#"COALESCE(
+                    (
+                        SELECT oh.probability
+                        FROM odds_history oh
+                        WHERE oh.source_market_id = sm.id
+                        AND oh.time <= COALESCE(m.resolution_date, m.updated_at) - $2::interval
+                        ORDER BY oh.time DESC
+                        LIMIT 1
+                    ),
+                    sm.current_probability
+                )"#,
+            // Each point holds until the next one, the last until resolution
+            SourceHorizon::TimeAverage => r#"(
+                    SELECT (SUM(w.probability * w.seconds) / NULLIF(SUM(w.seconds), 0))::DECIMAL(10, 6)
+                    FROM (
+                        SELECT
+                            oh.probability,
+                            EXTRACT(EPOCH FROM
+                                LEAD(oh.time, 1, COALESCE(m.resolution_date, m.updated_at)) OVER (ORDER BY oh.time)
+                                - oh.time
+                            ) as seconds
+                        FROM odds_history oh
+                        WHERE oh.source_market_id = sm.id
+                        AND oh.time <= COALESCE(m.resolution_date, m.updated_at) - $2::interval
+                    ) w
+                )"#,
+            SourceHorizon::Before(..) => r#"(
+                    SELECT oh.probability
+                    FROM odds_history oh
+                    WHERE oh.source_market_id = sm.id
+                    AND oh.time <= COALESCE(m.resolution_date, m.updated_at) - $2::interval
+                    ORDER BY oh.time DESC
+                    LIMIT 1
+                )"#,
+        }
+    }
+}
+
+/// Learned weight set versions kept; older ones are pruned after each fit.
+const WEIGHT_SET_VERSIONS_KEPT: i32 = 20;
+
+/// Extremizing fits kept; older ones are pruned after each fit.
+const EXTREMIZING_FITS_KEPT: i64 = 20;
+
+/// Scored horizons recalibration curves are fitted on. The final quote is left
+/// out, since by then the outcome is usually priced in.
+const RECALIBRATION_HORIZONS: [&str; 4] = ["1d", "7d", "30d", "90d"];
+
+/// A source and category (`None` = all categories).
+type SourceCategory = (Uuid, Option<Uuid>);
+
+pub async fn run_scoring_worker(pool: PgPool) -> Result<()> {
+    // Wait for initial data
+    tokio::time::sleep(std::time::Duration::from_secs(150)).await;
+
+    loop {
+        match score_resolved_markets(&pool).await {
+            Ok(count) => {
+                if count > 0 {
+                    info!("Scored {} forecasts on resolved markets", count);
+                }
+            }
+            Err(e) => warn!("Scoring error: {}", e),
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(SCORING_INTERVAL_SECS)).await;
+    }
+}
+
+async fn score_resolved_markets(pool: &PgPool) -> Result<usize> {
+    let source_count = score_source_predictions(pool).await?;
+    let user_count = score_user_forecasts(pool).await?;
+    let consensus_count = score_consensus(pool).await?;
+
+    // Rolling windows move even without new scores
+    refresh_accuracy_records(pool).await?;
+    if source_count > 0 {
+        refit_extremizing_exponent(pool).await?;
+        refit_source_weights(pool).await?;
+        refit_recalibrations(pool).await?;
+    }
+
+    Ok(source_count + user_count + consensus_count)
+}
+
+/// Score every source market on a resolved market at each horizon it has not
+/// been scored at yet.
+async fn score_source_predictions(pool: &PgPool) -> Result<usize> {
+    #[derive(sqlx::FromRow)]
+    struct Row {
+        source_market_id: Uuid,
+        source_id: Uuid,
+        market_id: Uuid,
+        category_id: Option<Uuid>,
+        predicted: BigDecimal,
+        outcome: BigDecimal,
+        resolved_at: DateTime<Utc>,
+    }
+
+    let mut count = 0;
+    for horizon in SOURCE_HORIZONS {
+        // No odds recorded that early means the source was not quoting yet;
+        // those are left out here rather than re-fetched every cycle
+        let query = format!(
+            r#"
+            SELECT * FROM (
+                SELECT
+                    sm.id as source_market_id,
+                    sm.source_id,
+                    m.id as market_id,
+                    m.category_id,
+                    {predicted} as predicted,
+                    m.resolution_value as outcome,
+                    COALESCE(m.resolution_date, m.updated_at) as resolved_at
+                FROM source_markets sm
+                JOIN markets m ON sm.market_id = m.id
+                WHERE m.status = 'resolved'
+                AND m.resolution_value IS NOT NULL
+                AND NOT EXISTS (
+                    SELECT 1 FROM prediction_scores ps WHERE ps.source_market_id = sm.id AND ps.horizon = $1
+                )
+            ) candidates
+            WHERE candidates.predicted IS NOT NULL
+            "#,
+            predicted = horizon.predicted_sql()
+        );
+        let rows = sqlx::query_as::<_, Row>(&query)
+            .bind(horizon.label())
+            .bind(horizon.interval())
+            .fetch_all(pool)
+            .await?;
+
+        for row in rows {
+            let (score, log_score, spherical_score) = PredictionScores::decimal(&row.predicted, &row.outcome)?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO prediction_scores
+                    (source_market_id, source_id, market_id, category_id, horizon, predicted_probability,
+                     actual_outcome, brier_score, log_score, spherical_score, resolved_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                ON CONFLICT (source_market_id, horizon) DO NOTHING
+                "#
+            )
+            .bind(row.source_market_id)
+            .bind(row.source_id)
+            .bind(row.market_id)
+            .bind(row.category_id)
+            .bind(horizon.label())
+            .bind(&row.predicted)
+            .bind(&row.outcome)
+            .bind(&score)
+            .bind(&log_score)
+            .bind(&spherical_score)
+            .bind(row.resolved_at)
+            .execute(pool)
+            .await?;
+
+            count += 1;
+        }
+    }
+
+    Ok(count)
+}
+
+/// Score each user's last forecast before resolution on every resolved market.
+async fn score_user_forecasts(pool: &PgPool) -> Result<usize> {
+    #[derive(sqlx::FromRow)]
+    struct Row {
+        user_id: Uuid,
+        market_id: Uuid,
+        category_id: Option<Uuid>,
+        predicted: BigDecimal,
+        outcome: BigDecimal,
+        resolved_at: DateTime<Utc>,
+    }
+
+    let rows = sqlx::query_as::<_, Row>(
+        r#"
+        SELECT DISTINCT ON (uf.user_id, uf.market_id)
+            uf.user_id,
+            m.id as market_id,
+            m.category_id,
+            uf.probability as predicted,
+            m.resolution_value as outcome,
+            COALESCE(m.resolution_date, m.updated_at) as resolved_at
+        FROM user_forecasts uf
+        JOIN markets m ON uf.market_id = m.id
+        WHERE m.status = 'resolved'
+        AND m.resolution_value IS NOT NULL
+        AND uf.created_at <= COALESCE(m.resolution_date, NOW())
+        AND NOT EXISTS (
+            SELECT 1 FROM user_prediction_scores ups
+            WHERE ups.user_id = uf.user_id AND ups.market_id = uf.market_id
+        )
+        ORDER BY uf.user_id, uf.market_id, uf.created_at DESC
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut count = 0;
+    for row in rows {
+        let score = brier::brier_score_decimal(&row.predicted, &row.outcome)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO user_prediction_scores
+                (user_id, market_id, category_id, predicted_probability, actual_outcome, brier_score, resolved_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (user_id, market_id) DO NOTHING
+            "#
+        )
+        .bind(row.user_id)
+        .bind(row.market_id)
+        .bind(row.category_id)
+        .bind(&row.predicted)
+        .bind(&row.outcome)
+        .bind(&score)
+        .bind(row.resolved_at)
+        .execute(pool)
+        .await?;
+
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+/// Score the consensus on every resolved market at each horizon, using the
+/// last snapshot at least that long before resolution.
+async fn score_consensus(pool: &PgPool) -> Result<usize> {
+    #[derive(sqlx::FromRow)]
+    struct Row {
+        market_id: Uuid,
+        category_id: Option<Uuid>,
+        snapshot_time: DateTime<Utc>,
+        predicted: BigDecimal,
+        extremized: Option<BigDecimal>,
+        outcome: BigDecimal,
+        resolved_at: DateTime<Utc>,
+    }
+
+    let mut count = 0;
+    for (horizon, interval) in CONSENSUS_HORIZONS {
+        let rows = sqlx::query_as::<_, Row>(
+            r#"
+            SELECT
+                m.id as market_id,
+                m.category_id,
+                cs.time as snapshot_time,
+                cs.consensus_probability as predicted,
+                cs.extremized_probability as extremized,
+                m.resolution_value as outcome,
+                COALESCE(m.resolution_date, m.updated_at) as resolved_at
+            FROM markets m
+            JOIN LATERAL (
+                SELECT time, consensus_probability, extremized_probability
+                FROM consensus_snapshots
+                WHERE market_id = m.id
+                AND time <= COALESCE(m.resolution_date, m.updated_at) - $1::interval
+                ORDER BY time DESC
+                LIMIT 1
+            ) cs ON true
+            WHERE m.status = 'resolved'
+            AND m.resolution_value IS NOT NULL
+            AND NOT EXISTS (
+                SELECT 1 FROM consensus_scores s WHERE s.market_id = m.id AND s.horizon = $2
+            )
+            "#
+        )
+        .bind(interval)
+        .bind(horizon)
+        .fetch_all(pool)
+        .await?;
+
+        for row in rows {
+            let (score, log_score, spherical_score) = PredictionScores::decimal(&row.predicted, &row.outcome)?;
+            let extremized_score = match &row.extremized {
+                Some(p) => Some(brier::brier_score_decimal(p, &row.outcome)?),
+                None => None,
+            };
+
+            sqlx::query(
+                r#"
+                INSERT INTO consensus_scores
+                    (market_id, category_id, horizon, snapshot_time, predicted_probability, extremized_probability,
+                     actual_outcome, brier_score, log_score, spherical_score, extremized_brier_score, resolved_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                ON CONFLICT (market_id, horizon) DO NOTHING
+                "#
+            )
+            .bind(row.market_id)
+            .bind(row.category_id)
+            .bind(horizon)
+            .bind(row.snapshot_time)
+            .bind(&row.predicted)
+            .bind(&row.extremized)
+            .bind(&row.outcome)
+            .bind(&score)
+            .bind(&log_score)
+            .bind(&spherical_score)
+            .bind(&extremized_score)
+            .bind(row.resolved_at)
+            .execute(pool)
+            .await?;
+
+            count += 1;
+        }
+    }
+
+    Ok(count)
+}
+
+/// Rebuild the per-category and overall (NULL category) accuracy records at
+/// every horizon, all time and over each rolling window, from all scored
+/// source predictions. Rolling records of sources with no scores left in the
+/// window are removed.
+async fn refresh_accuracy_records(pool: &PgPool) -> Result<()> {
+    let windows = std::iter::once(None).chain(ACCURACY_WINDOWS_DAYS.iter().copied().map(Some));
+    for window_days in windows {
+        // NOW() is fixed for the transaction, so rows not touched by the
+        // upsert are exactly the ones to drop
+        let mut tx = pool.begin().await?;
+        sqlx::query(
+            r#"
+            WITH scores AS (
+                SELECT * FROM prediction_scores
+                WHERE $1::int IS NULL OR resolved_at >= NOW() - make_interval(days => $1::int)
+            )
+            INSERT INTO accuracy_records
+                (source_id, category_id, horizon, window_days, total_resolved, correct_predictions, brier_score,
+                 accuracy_pct, log_score, spherical_score, brier_skill_score, climatology_skill_score, last_calculated_at)
+            SELECT
+                source_id,
+                category_id,
+                horizon,
+                $1,
+                COUNT(*),
+                COUNT(*) FILTER (WHERE (predicted_probability >= 0.5) = (actual_outcome >= 0.5)),
+                AVG(brier_score),
+                (1 - AVG(brier_score)) * 100,
+                AVG(log_score),
+                AVG(spherical_score),
+                1 - AVG(brier_score) / 0.25,
+                1 - AVG(brier_score) / NULLIF(AVG(actual_outcome) * (1 - AVG(actual_outcome)), 0),
+                NOW()
+            FROM scores
+            WHERE category_id IS NOT NULL
+            GROUP BY source_id, category_id, horizon
+            UNION ALL
+            SELECT
+                source_id,
+                NULL,
+                horizon,
+                $1,
+                COUNT(*),
+                COUNT(*) FILTER (WHERE (predicted_probability >= 0.5) = (actual_outcome >= 0.5)),
+                AVG(brier_score),
+                (1 - AVG(brier_score)) * 100,
+                AVG(log_score),
+                AVG(spherical_score),
+                1 - AVG(brier_score) / 0.25,
+                1 - AVG(brier_score) / NULLIF(AVG(actual_outcome) * (1 - AVG(actual_outcome)), 0),
+                NOW()
+            FROM scores
+            GROUP BY source_id, horizon
+            ON CONFLICT (source_id, category_id, horizon, window_days) DO UPDATE SET
+                total_resolved = EXCLUDED.total_resolved,
+                correct_predictions = EXCLUDED.correct_predictions,
+                brier_score = EXCLUDED.brier_score,
+                accuracy_pct = EXCLUDED.accuracy_pct,
+                log_score = EXCLUDED.log_score,
+                spherical_score = EXCLUDED.spherical_score,
+                brier_skill_score = EXCLUDED.brier_skill_score,
+                climatology_skill_score = EXCLUDED.climatology_skill_score,
+                last_calculated_at = EXCLUDED.last_calculated_at,
+                updated_at = NOW()
+            "#
+        )
+        .bind(window_days)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "DELETE FROM accuracy_records WHERE window_days IS NOT DISTINCT FROM $1 AND last_calculated_at < NOW()"
+        )
+        .bind(window_days)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+    }
+
+    Ok(())
+}
+
+/// Fit the extremizing exponent on the last multi-source consensus snapshot
+/// a fixed lead time before resolution of every resolved market.
+async fn refit_extremizing_exponent(pool: &PgPool) -> Result<()> {
+    #[derive(sqlx::FromRow)]
+    struct Row {
+        weights: serde_json::Value,
+        outcome: BigDecimal,
+    }
+
+    let rows = sqlx::query_as::<_, Row>(
+        r#"
+        SELECT cs.weights, m.resolution_value as outcome
+        FROM markets m
+        JOIN LATERAL (
+            SELECT weights
+            FROM consensus_snapshots
+            WHERE market_id = m.id
+            AND time <= COALESCE(m.resolution_date, m.updated_at) - $1::interval
+            AND source_count >= 2
+            ORDER BY time DESC
+            LIMIT 1
+        ) cs ON true
+        WHERE m.status = 'resolved'
+        AND m.resolution_value IS NOT NULL
+        "#
+    )
+    .bind(format!("{} hours", extremize::EXTREMIZING_FIT_HORIZON_HOURS))
+    .fetch_all(pool)
+    .await?;
+
+    let samples: Vec<(f64, f64)> = rows.into_iter()
+        .filter_map(|r| {
+            let weights: Vec<SourceWeight> = serde_json::from_value(r.weights).ok()?;
+            let probabilities: Vec<f64> = weights.iter().map(|w| w.probability).collect();
+            let ws: Vec<f64> = weights.iter().map(|w| w.weight).collect();
+            let outcome: f64 = r.outcome.to_string().parse().ok()?;
+            Some((LogOddsPool.aggregate(&probabilities, &ws), outcome))
+        })
+        .collect();
+
+    let Some(fit) = extremize::fit_extremizing_exponent(&samples) else {
+        return Ok(());
+    };
+
+    sqlx::query(
+        r#"
+        INSERT INTO extremizing_fits (exponent, sample_size, log_loss_raw, log_loss_extremized)
+        VALUES ($1, $2, $3, $4)
+        "#
+    )
+    .bind(BigDecimal::from_str(&format!("{:.4}", fit.exponent))?)
+    .bind(fit.sample_size as i32)
+    .bind(BigDecimal::from_str(&format!("{:.6}", fit.log_loss_raw))?)
+    .bind(BigDecimal::from_str(&format!("{:.6}", fit.log_loss_extremized))?)
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        DELETE FROM extremizing_fits
+        WHERE id NOT IN (SELECT id FROM extremizing_fits ORDER BY fitted_at DESC LIMIT $1)
+        "#
+    )
+    .bind(EXTREMIZING_FITS_KEPT)
+    .execute(pool)
+    .await?;
+
+    info!(
+        "Fitted extremizing exponent {:.3} on {} resolved markets (log loss {:.4} -> {:.4})",
+        fit.exponent, fit.sample_size, fit.log_loss_raw, fit.log_loss_extremized
+    );
+
+    Ok(())
+}
+
+/// Fit learned source weights overall and per category, and store them as a
+/// new version of `source_weight_sets`.
+async fn refit_source_weights(pool: &PgPool) -> Result<()> {
+    #[derive(sqlx::FromRow)]
+    struct Row {
+        market_id: Uuid,
+        category_id: Option<Uuid>,
+        source_slug: String,
+        probability: BigDecimal,
+        outcome: BigDecimal,
+    }
+
+    let rows = sqlx::query_as::<_, Row>(
+        r#"
+        SELECT m.id as market_id, m.category_id, s.slug as source_slug, oh.probability, m.resolution_value as outcome
+        FROM markets m
+        JOIN source_markets sm ON sm.market_id = m.id
+        JOIN sources s ON sm.source_id = s.id
+        JOIN LATERAL (
+            SELECT probability
+            FROM odds_history
+            WHERE source_market_id = sm.id
+            AND time <= COALESCE(m.resolution_date, m.updated_at) - $1::interval
+            ORDER BY time DESC
+            LIMIT 1
+        ) oh ON true
+        WHERE m.status = 'resolved'
+        AND m.resolution_value IS NOT NULL
+        "#
+    )
+    .bind(format!("{} hours", learned::WEIGHT_FIT_HORIZON_HOURS))
+    .fetch_all(pool)
+    .await?;
+
+    let mut markets: BTreeMap<Uuid, (Option<Uuid>, WeightSample)> = BTreeMap::new();
+    for row in rows {
+        let (Ok(probability), Ok(outcome)) = (row.probability.to_string().parse(), row.outcome.to_string().parse()) else {
+            continue;
+        };
+        markets.entry(row.market_id)
+            .or_insert_with(|| (row.category_id, WeightSample { forecasts: Vec::new(), outcome }))
+            .1.forecasts.push((row.source_slug, probability));
+    }
+
+    // Fitting is CPU-bound, keep it off the async workers
+    let fits = tokio::task::spawn_blocking(move || {
+        let mut by_category: BTreeMap<Option<Uuid>, Vec<WeightSample>> = BTreeMap::new();
+        for (category_id, sample) in markets.into_values() {
+            if category_id.is_some() {
+                by_category.entry(category_id).or_default().push(sample.clone());
+            }
+            by_category.entry(None).or_default().push(sample);
+        }
+        by_category.into_iter()
+            .filter_map(|(category_id, samples)| {
+                learned::fit_source_weights(&samples, FitObjective::LogLoss).map(|fit| (category_id, fit))
+            })
+            .collect::<Vec<(Option<Uuid>, LearnedWeights)>>()
+    })
+    .await?;
+
+    if fits.is_empty() {
+        return Ok(());
+    }
+
+    let mut tx = pool.begin().await?;
+    let version: i32 = sqlx::query_scalar("SELECT COALESCE(MAX(version), 0) + 1 FROM source_weight_sets")
+        .fetch_one(&mut *tx)
+        .await?;
+
+    for (category_id, fit) in &fits {
+        sqlx::query(
+            r#"
+            INSERT INTO source_weight_sets
+                (version, category_id, objective, regularization, sample_size, cv_loss, baseline_loss, weights)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#
+        )
+        .bind(version)
+        .bind(category_id)
+        .bind(fit.objective.as_str())
+        .bind(BigDecimal::from_str(&format!("{:.6}", fit.regularization))?)
+        .bind(fit.sample_size as i32)
+        .bind(BigDecimal::from_str(&format!("{:.6}", fit.cv_loss))?)
+        .bind(BigDecimal::from_str(&format!("{:.6}", fit.baseline_loss))?)
+        .bind(serde_json::to_value(&fit.weights)?)
+        .execute(&mut *tx)
+        .await?;
+    }
+    sqlx::query("DELETE FROM source_weight_sets WHERE version <= $1")
+        .bind(version - WEIGHT_SET_VERSIONS_KEPT)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    let overall = fits.iter().find(|(category_id, _)| category_id.is_none());
+    if let Some((_, fit)) = overall {
+        info!(
+            "Fitted source weights v{} on {} markets (log loss {:.4} vs {:.4} equal): {:?}",
+            version, fit.sample_size, fit.cv_loss, fit.baseline_loss, fit.weights
+        );
+    }
+
+    Ok(())
+}
+
+/// Fit each source's recalibration curve overall and per category on its
+/// scored forecasts, replacing the previous curves.
+async fn refit_recalibrations(pool: &PgPool) -> Result<()> {
+    let run_start = Utc::now();
+    let rows: Vec<(Uuid, Uuid, Option<Uuid>, BigDecimal, BigDecimal)> = sqlx::query_as(
+        r#"
+        SELECT market_id, source_id, category_id, predicted_probability, actual_outcome
+        FROM prediction_scores
+        WHERE horizon = ANY($1)
+        ORDER BY market_id, horizon
+        "#
+    )
+    .bind(&RECALIBRATION_HORIZONS[..])
+    .fetch_all(pool)
+    .await?;
+
+    // (source, category) -> (quoted, outcome) per market, its horizons kept
+    // together so cross-validation folds by market; category None = all
+    let mut samples: BTreeMap<SourceCategory, Vec<Vec<(f64, f64)>>> = BTreeMap::new();
+    let mut last_market: BTreeMap<SourceCategory, Uuid> = BTreeMap::new();
+    for (market_id, source_id, category_id, predicted, outcome) in rows {
+        let (Ok(predicted), Ok(outcome)) = (predicted.to_string().parse(), outcome.to_string().parse()) else {
+            continue;
+        };
+        let keys = [category_id.map(|c| (source_id, Some(c))), Some((source_id, None))];
+        for key in keys.into_iter().flatten() {
+            let markets = samples.entry(key).or_default();
+            if last_market.insert(key, market_id) != Some(market_id) {
+                markets.push(Vec::new());
+            }
+            if let Some(market) = markets.last_mut() {
+                market.push((predicted, outcome));
+            }
+        }
+    }
+
+    let fits = tokio::task::spawn_blocking(move || {
+        samples.into_iter()
+            .filter_map(|(key, samples)| recalibration::fit_recalibration(&samples).map(|fit| (key, fit)))
+            .collect::<Vec<(SourceCategory, RecalibrationFit)>>()
+    })
+    .await?;
+
+    for ((source_id, category_id), fit) in &fits {
+        sqlx::query(
+            r#"
+            INSERT INTO source_recalibrations
+                (source_id, category_id, method, curve, sample_size, cv_loss, baseline_loss, fitted_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (source_id, category_id) DO UPDATE SET
+                method = EXCLUDED.method,
+                curve = EXCLUDED.curve,
+                sample_size = EXCLUDED.sample_size,
+                cv_loss = EXCLUDED.cv_loss,
+                baseline_loss = EXCLUDED.baseline_loss,
+                fitted_at = EXCLUDED.fitted_at
+            "#
+        )
+        .bind(source_id)
+        .bind(category_id)
+        .bind(fit.recalibration.method())
+        .bind(serde_json::to_value(&fit.recalibration)?)
+        .bind(fit.sample_size as i32)
+        .bind(BigDecimal::from_str(&format!("{:.6}", fit.cv_loss))?)
+        .bind(BigDecimal::from_str(&format!("{:.6}", fit.baseline_loss))?)
+        .bind(Utc::now())
+        .execute(pool)
+        .await?;
+    }
+
+    sqlx::query("DELETE FROM source_recalibrations WHERE fitted_at < $1")
+        .bind(run_start)
+        .execute(pool)
+        .await?;
+
+    let improved = fits.iter().filter(|(_, fit)| fit.beats_baseline()).count();
+    if !fits.is_empty() {
+        info!("Fitted {} recalibration curves, {} beat the raw quotes", fits.len(), improved);
+    }
+
+    Ok(())
+}
@@ -4,7 +4,7 @@ use serde::Deserialize;
 use sqlx::PgPool;
 use tracing::{info, warn, error};
 
-use crate::ingestion;
+use crate::ingestion::{self, SourceMarketUpdate};
 
 const MANIFOLD_API: &str = "https://api.manifold.markets/v0";
 const POLL_INTERVAL_SECS: u64 = 600; // 10 minutes
@@ -84,7 +84,7 @@ async fn process_market(pool: &PgPool, market: &ManifoldMarket) -> Result<()> {
     let probability = market.probability.unwrap_or(0.5);
 
     let external_url = market.url.as_deref()
-        .or(market.slug.as_ref().map(|s| s.as_str()))
+        .or(market.slug.as_deref())
         .map(|u| {
             if u.starts_with("http") { u.to_string() }
             else { format!("https://manifold.markets/{}", u) }
@@ -95,16 +95,19 @@ async fn process_market(pool: &PgPool, market: &ManifoldMarket) -> Result<()> {
         "outcome_type": market.outcome_type,
     });
 
-    let source_market_id = ingestion::upsert_source_market(
-        pool,
-        "manifold",
-        &market.id,
-        question,
+    let source_market_id = ingestion::upsert_source_market(pool, SourceMarketUpdate {
+        source_slug: "manifold",
+        external_id: &market.id,
+        title: question,
         probability,
-        market.volume,
-        external_url.as_deref(),
+        volume: market.volume,
+        liquidity: market.total_liquidity,
+        spread: None,
+        best_bid: None,
+        best_ask: None,
+        external_url: external_url.as_deref(),
         metadata,
-    ).await?;
+    }).await?;
 
     let slug = format!("mf-{}", slug_from_title(question));
 
@@ -4,7 +4,7 @@ use serde::Deserialize;
 use sqlx::PgPool;
 use tracing::{info, warn, error};
 
-use crate::ingestion;
+use crate::ingestion::{self, SourceMarketUpdate};
 
 const METACULUS_API: &str = "https://www.metaculus.com/api2";
 const POLL_INTERVAL_SECS: u64 = 600; // 10 minutes
@@ -146,16 +146,19 @@ async fn process_question(pool: &PgPool, question: &MetaculusQuestion) -> Result
         "forecasters": forecasters,
     });
 
-    let source_market_id = ingestion::upsert_source_market(
-        pool,
-        "metaculus",
-        &external_id,
+    let source_market_id = ingestion::upsert_source_market(pool, SourceMarketUpdate {
+        source_slug: "metaculus",
+        external_id: &external_id,
         title,
         probability,
-        None,
-        external_url.as_deref(),
+        volume: None,
+        liquidity: None,
+        spread: None,
+        best_bid: None,
+        best_ask: None,
+        external_url: external_url.as_deref(),
         metadata,
-    ).await?;
+    }).await?;
 
     let slug = format!("mc-{}", slug_from_title(title));
 
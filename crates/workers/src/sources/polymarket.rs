@@ -4,7 +4,7 @@ use serde::Deserialize;
 use sqlx::PgPool;
 use tracing::{info, warn, error};
 
-use crate::ingestion;
+use crate::ingestion::{self, SourceMarketUpdate};
 
 const GAMMA_API: &str = "https://gamma-api.polymarket.com";
 const POLL_INTERVAL_SECS: u64 = 300; // 5 minutes
@@ -20,6 +20,11 @@ struct GammaMarket {
     volume_num: Option<f64>,
     #[serde(rename = "liquidityNum")]
     liquidity_num: Option<f64>,
+    spread: Option<f64>,
+    #[serde(rename = "bestBid")]
+    best_bid: Option<f64>,
+    #[serde(rename = "bestAsk")]
+    best_ask: Option<f64>,
     slug: Option<String>,
     active: Option<bool>,
     closed: Option<bool>,
@@ -118,16 +123,19 @@ async fn process_market(pool: &PgPool, market: &GammaMarket) -> Result<()> {
         "liquidity": market.liquidity_num,
     });
 
-    let source_market_id = ingestion::upsert_source_market(
-        pool,
-        "polymarket",
+    let source_market_id = ingestion::upsert_source_market(pool, SourceMarketUpdate {
+        source_slug: "polymarket",
         external_id,
-        question,
+        title: question,
         probability,
-        market.volume_num,
-        external_url.as_deref(),
+        volume: market.volume_num,
+        liquidity: market.liquidity_num,
+        spread: market.spread,
+        best_bid: market.best_bid,
+        best_ask: market.best_ask,
+        external_url: external_url.as_deref(),
         metadata,
-    ).await?;
+    }).await?;
 
     // Create slug from question
     let slug = format!("pm-{}", slug_from_title(question));
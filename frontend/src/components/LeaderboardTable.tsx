@@ -19,7 +19,7 @@ export default function LeaderboardTable({ entries }: Props) {
         </thead>
         <tbody className="divide-y divide-gray-50">
           {entries.map((entry) => (
-            <tr key={entry.source_slug} className="hover:bg-gray-50 transition-colors">
+            <tr key={entry.source_slug} className={`hover:bg-gray-50 transition-colors ${entry.is_consensus ? "bg-blue-50/50" : ""}`}>
               <td className="px-6 py-4">
                 <span className={`inline-flex items-center justify-center w-7 h-7 rounded-full text-xs font-bold ${
                   entry.rank === 1 ? "bg-amber-100 text-amber-700" :
@@ -27,7 +27,7 @@ export default function LeaderboardTable({ entries }: Props) {
                   entry.rank === 3 ? "bg-orange-100 text-orange-700" :
                   "bg-gray-50 text-gray-500"
                 }`}>
-                  {entry.rank}
+                  {entry.tied ? `=${entry.rank}` : entry.rank}
                 </span>
               </td>
               <td className="px-6 py-4 font-medium text-navy">{entry.source_name}</td>
@@ -9,6 +9,7 @@ export interface Market {
   status: string;
   consensus_probability: number | null;
   source_count: number;
+  volume_usd: number | null;
   updated_at: string;
 }
 
@@ -41,6 +42,8 @@ export interface SourceMarketSummary {
   source_slug: string;
   probability: number | null;
   volume: number | null;
+  currency: string | null;
+  volume_usd: number | null;
   accuracy_pct: number | null;
   external_url: string | null;
 }
@@ -57,6 +60,7 @@ export interface OddsPoint {
   source_market_id: string;
   probability: number;
   volume: number | null;
+  currency: string | null;
 }
 
 export interface AccuracyEntry {
@@ -65,7 +69,17 @@ export interface AccuracyEntry {
   source_slug: string;
   accuracy_pct: number | null;
   brier_score: number | null;
+  log_score: number | null;
+  spherical_score: number | null;
+  brier_skill_score: number | null;
+  climatology_skill_score: number | null;
   total_resolved: number;
+  is_consensus: boolean;
+  score: number | null;
+  std_error: number | null;
+  ci_lower: number | null;
+  ci_upper: number | null;
+  tied: boolean;
 }
 
 export interface ApiResponse<T> {
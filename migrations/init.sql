@@ -7,6 +7,25 @@ EXCEPTION WHEN OTHERS THEN
 END $$;
 CREATE EXTENSION IF NOT EXISTS "uuid-ossp";
 
+-- Units volumes and liquidity are reported in, and their USD value. Set
+-- usd_rate to convert a currency into USD figures, or NULL to leave it out.
+CREATE TABLE currencies (
+    code VARCHAR(10) PRIMARY KEY, -- 'USD', 'USDC', 'MANA'
+    name VARCHAR(100) NOT NULL,
+    usd_rate DECIMAL(20, 10), -- USD per unit; NULL = excluded from USD-normalized figures
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+INSERT INTO currencies (code, name, usd_rate) VALUES
+    ('USD', 'US Dollar', 1),
+    ('USDC', 'USD Coin', 1),
+    ('MANA', 'Manifold mana', NULL); -- play money, no cash value
+
+-- An amount in USD, or NULL when the currency is unknown or excluded
+CREATE FUNCTION to_usd(amount DECIMAL, currency VARCHAR) RETURNS DECIMAL AS $$
+    SELECT amount * usd_rate FROM currencies WHERE code = currency
+$$ LANGUAGE SQL STABLE;
+
 -- Sources (Polymarket, Kalshi, Metaculus, etc.)
 CREATE TABLE sources (
     id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
@@ -14,6 +33,9 @@ CREATE TABLE sources (
     name VARCHAR(255) NOT NULL,
     source_type VARCHAR(50) NOT NULL, -- 'prediction_market', 'forecast_platform', 'analyst'
     api_base_url TEXT,
+    play_money BOOLEAN NOT NULL DEFAULT false, -- traded in a currency with no cash value (e.g. mana)
+    fee_model JSONB, -- e.g. {"name": "kalshi_taker", "rate": 0.07}; NULL = not tradable
+    currency VARCHAR(10) NOT NULL DEFAULT 'USD' REFERENCES currencies(code), -- unit of reported volume and liquidity
     is_active BOOLEAN DEFAULT true,
     created_at TIMESTAMPTZ DEFAULT NOW(),
     updated_at TIMESTAMPTZ DEFAULT NOW()
@@ -39,6 +61,7 @@ CREATE TABLE markets (
     status VARCHAR(50) DEFAULT 'active', -- 'active', 'resolved', 'closed', 'cancelled'
     resolution_value DECIMAL(10, 6), -- NULL if unresolved, 0-1 for resolved
     resolution_date TIMESTAMPTZ,
+    consensus_config JSONB, -- NULL = default aggregation, e.g. {"aggregation": {"name": "trimmed_mean"}}
     created_at TIMESTAMPTZ DEFAULT NOW(),
     updated_at TIMESTAMPTZ DEFAULT NOW()
 );
@@ -52,8 +75,13 @@ CREATE TABLE source_markets (
     external_url TEXT,
     title TEXT NOT NULL,
     current_probability DECIMAL(10, 6),
+    probability_changed_at TIMESTAMPTZ DEFAULT NOW(), -- last time current_probability moved; sources are aged from this
     volume DECIMAL(20, 2),
     liquidity DECIMAL(20, 2),
+    currency VARCHAR(10) REFERENCES currencies(code), -- unit of volume and liquidity, see to_usd()
+    spread DECIMAL(10, 6),
+    best_bid DECIMAL(10, 6), -- best YES bid/ask, for order book venues
+    best_ask DECIMAL(10, 6),
     status VARCHAR(50) DEFAULT 'active',
     resolution_value DECIMAL(10, 6),
     resolution_date TIMESTAMPTZ,
@@ -69,6 +97,7 @@ CREATE TABLE odds_history (
     source_market_id UUID NOT NULL REFERENCES source_markets(id) ON DELETE CASCADE,
     probability DECIMAL(10, 6) NOT NULL,
     volume DECIMAL(20, 2),
+    currency VARCHAR(10), -- unit of volume, see to_usd()
     trade_count INTEGER
 );
 
@@ -82,19 +111,25 @@ END $$;
 -- Create index for efficient lookups
 CREATE INDEX idx_odds_history_source_market ON odds_history (source_market_id, time DESC);
 
--- Accuracy Records (per source, per category)
+-- Accuracy Records (per source, per category, per scoring horizon, per window)
 CREATE TABLE accuracy_records (
     id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
     source_id UUID NOT NULL REFERENCES sources(id) ON DELETE CASCADE,
     category_id UUID REFERENCES categories(id),
+    horizon VARCHAR(10) NOT NULL DEFAULT 'final', -- see prediction_scores.horizon
+    window_days INTEGER, -- rolling window the record covers, NULL = all time
     total_resolved INTEGER DEFAULT 0,
     correct_predictions INTEGER DEFAULT 0,
     brier_score DECIMAL(10, 6),
     accuracy_pct DECIMAL(10, 4),
+    log_score DECIMAL(10, 6), -- mean ln(p of outcome), higher is better
+    spherical_score DECIMAL(10, 6), -- higher is better
+    brier_skill_score DECIMAL(10, 6), -- 1 - brier / 0.25 (always 50%)
+    climatology_skill_score DECIMAL(10, 6), -- 1 - brier / (base rate * (1 - base rate))
     last_calculated_at TIMESTAMPTZ,
     created_at TIMESTAMPTZ DEFAULT NOW(),
     updated_at TIMESTAMPTZ DEFAULT NOW(),
-    UNIQUE(source_id, category_id)
+    UNIQUE NULLS NOT DISTINCT (source_id, category_id, horizon, window_days) -- NULL category = overall record
 );
 
 -- Individual prediction scores (for Brier score calculation)
@@ -104,11 +139,18 @@ CREATE TABLE prediction_scores (
     source_id UUID NOT NULL REFERENCES sources(id) ON DELETE CASCADE,
     market_id UUID NOT NULL REFERENCES markets(id) ON DELETE CASCADE,
     category_id UUID REFERENCES categories(id),
-    predicted_probability DECIMAL(10, 6) NOT NULL, -- probability at time of resolution
+    -- 'final': last probability before resolution; 'time_avg': time-weighted mean
+    -- over the market's life; '1d', '7d', '30d', '90d': last probability at least
+    -- that long before resolution
+    horizon VARCHAR(10) NOT NULL DEFAULT 'final',
+    predicted_probability DECIMAL(10, 6) NOT NULL,
     actual_outcome DECIMAL(10, 6) NOT NULL, -- 0 or 1
     brier_score DECIMAL(10, 6) NOT NULL, -- (predicted - actual)^2
+    log_score DECIMAL(10, 6) NOT NULL, -- ln(p of outcome), p clamped to [1e-4, 1 - 1e-4]
+    spherical_score DECIMAL(10, 6) NOT NULL, -- p of outcome / sqrt(p^2 + (1 - p)^2)
     resolved_at TIMESTAMPTZ NOT NULL,
-    created_at TIMESTAMPTZ DEFAULT NOW()
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    UNIQUE(source_market_id, horizon)
 );
 
 -- Consensus snapshots
@@ -119,8 +161,15 @@ CREATE TABLE consensus_snapshots (
     confidence_score DECIMAL(10, 4),
     source_count INTEGER NOT NULL,
     agreement_score DECIMAL(10, 4), -- 0-1, how much sources agree
+    interval_lower DECIMAL(10, 6), -- bootstrap interval around consensus_probability
+    interval_upper DECIMAL(10, 6),
     outlier_sources JSONB DEFAULT '[]',
     weights JSONB NOT NULL, -- {"source_id": weight, ...}
+    aggregation_method VARCHAR(50) NOT NULL DEFAULT 'linear_pool',
+    aggregation_params JSONB NOT NULL DEFAULT '{}',
+    extremized_probability DECIMAL(10, 6), -- log-odds pool scaled by extremizing_exponent
+    extremizing_exponent DECIMAL(10, 4),
+    stale_sources JSONB NOT NULL DEFAULT '[]', -- sources left out for being too old
     created_at TIMESTAMPTZ DEFAULT NOW()
 );
 
@@ -130,6 +179,73 @@ EXCEPTION WHEN OTHERS THEN
   RAISE NOTICE 'TimescaleDB not available, using regular table for consensus_snapshots';
 END $$;
 
+-- Consensus forecasts scored on resolved markets, as a pseudo-source.
+-- 'final' is the last snapshot before resolution; '1d', '7d', '30d', '90d' the last
+-- snapshot at least that long before resolution.
+CREATE TABLE consensus_scores (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    market_id UUID NOT NULL REFERENCES markets(id) ON DELETE CASCADE,
+    category_id UUID REFERENCES categories(id),
+    horizon VARCHAR(10) NOT NULL,
+    snapshot_time TIMESTAMPTZ NOT NULL,
+    predicted_probability DECIMAL(10, 6) NOT NULL,
+    extremized_probability DECIMAL(10, 6),
+    actual_outcome DECIMAL(10, 6) NOT NULL,
+    brier_score DECIMAL(10, 6) NOT NULL,
+    log_score DECIMAL(10, 6) NOT NULL,
+    spherical_score DECIMAL(10, 6) NOT NULL,
+    extremized_brier_score DECIMAL(10, 6),
+    resolved_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    UNIQUE(market_id, horizon)
+);
+
+CREATE INDEX idx_consensus_scores_horizon ON consensus_scores (horizon, category_id);
+
+-- Extremizing exponents fitted on resolved markets, on the consensus a day
+-- before resolution (latest row is used, the last 20 are kept)
+CREATE TABLE extremizing_fits (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    exponent DECIMAL(10, 4) NOT NULL,
+    sample_size INTEGER NOT NULL,
+    log_loss_raw DECIMAL(10, 6),
+    log_loss_extremized DECIMAL(10, 6),
+    fitted_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- Learned source weights fitted on resolved markets. Rows written by one fit
+-- share a version; the consensus worker uses the latest version and the
+-- scoring worker keeps the last 20.
+CREATE TABLE source_weight_sets (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    version INTEGER NOT NULL,
+    category_id UUID REFERENCES categories(id) ON DELETE CASCADE, -- NULL = all categories
+    objective VARCHAR(20) NOT NULL, -- 'brier' or 'log_loss'
+    regularization DECIMAL(10, 6) NOT NULL,
+    sample_size INTEGER NOT NULL,
+    cv_loss DECIMAL(10, 6) NOT NULL, -- cross-validated loss of the learned weights
+    baseline_loss DECIMAL(10, 6) NOT NULL, -- loss of equal weights
+    weights JSONB NOT NULL, -- {"source_slug": weight, ...}, mean 1
+    fitted_at TIMESTAMPTZ DEFAULT NOW(),
+    UNIQUE NULLS NOT DISTINCT (version, category_id)
+);
+
+-- Per-source recalibration curves fitted on resolved prediction_scores,
+-- applied to the source's probability before pooling. Only curves with
+-- cv_loss < baseline_loss are used.
+CREATE TABLE source_recalibrations (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    source_id UUID NOT NULL REFERENCES sources(id) ON DELETE CASCADE,
+    category_id UUID REFERENCES categories(id) ON DELETE CASCADE, -- NULL = all categories
+    method VARCHAR(20) NOT NULL, -- 'platt' or 'isotonic'
+    curve JSONB NOT NULL, -- {"method": "platt", "slope": .., "intercept": ..} or {"method": "isotonic", "points": [[quoted, corrected], ...]}
+    sample_size INTEGER NOT NULL, -- resolved markets fitted on
+    cv_loss DECIMAL(10, 6) NOT NULL, -- cross-validated (folded by market) log loss of the corrected probabilities
+    baseline_loss DECIMAL(10, 6) NOT NULL, -- log loss of the raw quotes
+    fitted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE NULLS NOT DISTINCT (source_id, category_id)
+);
+
 -- Movement events (for "Why It Moved")
 CREATE TABLE movement_events (
     id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
@@ -144,6 +260,64 @@ CREATE TABLE movement_events (
     created_at TIMESTAMPTZ DEFAULT NOW()
 );
 
+-- Cross-venue arbitrage: YES on one venue plus NO on another, net of fees.
+-- A row stays open (closed_at NULL) while the opportunity persists.
+CREATE TABLE arbitrage_opportunities (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    market_id UUID NOT NULL REFERENCES markets(id) ON DELETE CASCADE,
+    yes_source_market_id UUID NOT NULL REFERENCES source_markets(id) ON DELETE CASCADE,
+    no_source_market_id UUID NOT NULL REFERENCES source_markets(id) ON DELETE CASCADE,
+    yes_price DECIMAL(10, 6) NOT NULL,
+    no_price DECIMAL(10, 6) NOT NULL,
+    fees DECIMAL(10, 6) NOT NULL,
+    edge DECIMAL(10, 6) NOT NULL, -- profit per unit of payout, after fees
+    return_pct DECIMAL(10, 4) NOT NULL,
+    max_edge DECIMAL(10, 6) NOT NULL,
+    executable BOOLEAN NOT NULL, -- both legs priced from a live bid/ask
+    play_money BOOLEAN NOT NULL,
+    detected_at TIMESTAMPTZ NOT NULL,
+    last_seen_at TIMESTAMPTZ NOT NULL,
+    closed_at TIMESTAMPTZ
+);
+
+-- Lead-lag between venues quoting the same markets, from odds_history changes
+-- on a common grid. Pairs are ordered by source slug; a positive lag means
+-- source_a moves first. category_id NULL = all categories.
+CREATE TABLE lead_lag_stats (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    source_a_id UUID NOT NULL REFERENCES sources(id) ON DELETE CASCADE,
+    source_b_id UUID NOT NULL REFERENCES sources(id) ON DELETE CASCADE,
+    category_id UUID REFERENCES categories(id) ON DELETE CASCADE,
+    markets INTEGER NOT NULL,
+    observations INTEGER NOT NULL,
+    window_days INTEGER NOT NULL,
+    step_minutes INTEGER NOT NULL, -- grid step, at least the slowest venue's median gap between polls
+    best_lag_minutes INTEGER NOT NULL,
+    best_correlation DECIMAL(10, 6) NOT NULL,
+    a_share DECIMAL(10, 6) NOT NULL, -- source_a's price discovery share (0.5 = neither leads)
+    correlations JSONB NOT NULL, -- [{"lag": steps, "correlation": r}, ...]
+    calculated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE NULLS NOT DISTINCT (source_a_id, source_b_id, category_id)
+);
+
+-- Dependence between sources (0 = independent, 1 = the same opinion): the mean
+-- of odds co-movement (peak lead-lag correlation) and the correlation of
+-- residuals (7 days out, against the other sources) on shared resolved
+-- markets. Pairs ordered by source id;
+-- category_id NULL = all categories.
+CREATE TABLE source_dependence (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    source_a_id UUID NOT NULL REFERENCES sources(id) ON DELETE CASCADE,
+    source_b_id UUID NOT NULL REFERENCES sources(id) ON DELETE CASCADE,
+    category_id UUID REFERENCES categories(id) ON DELETE CASCADE,
+    comovement DECIMAL(10, 6),
+    residual_correlation DECIMAL(10, 6),
+    shared_markets INTEGER NOT NULL DEFAULT 0, -- resolved markets behind residual_correlation
+    dependence DECIMAL(10, 6) NOT NULL,
+    calculated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE NULLS NOT DISTINCT (source_a_id, source_b_id, category_id)
+);
+
 -- Whale trades (for Polymarket on-chain tracking)
 CREATE TABLE whale_trades (
     id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
@@ -174,16 +348,51 @@ CREATE TABLE wallet_accuracy (
     updated_at TIMESTAMPTZ DEFAULT NOW()
 );
 
--- Users (for future auth)
+-- Teams
+CREATE TABLE teams (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    slug VARCHAR(100) UNIQUE NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- Users
 CREATE TABLE users (
     id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
     email VARCHAR(255) UNIQUE NOT NULL,
     name VARCHAR(255),
     tier VARCHAR(50) DEFAULT 'free', -- 'free', 'pro', 'team', 'enterprise'
+    is_admin BOOLEAN NOT NULL DEFAULT false, -- may change platform-wide settings
+    team_id UUID REFERENCES teams(id) ON DELETE SET NULL,
+    api_key_hash BYTEA UNIQUE, -- sha256 of the API bearer token; the token itself is not stored
     created_at TIMESTAMPTZ DEFAULT NOW(),
     updated_at TIMESTAMPTZ DEFAULT NOW()
 );
 
+-- User forecasts (append-only; the latest row per user/market is the current forecast)
+CREATE TABLE user_forecasts (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    market_id UUID NOT NULL REFERENCES markets(id) ON DELETE CASCADE,
+    probability DECIMAL(10, 6) NOT NULL,
+    comment TEXT,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- Scored user forecasts (one per user and resolved market)
+CREATE TABLE user_prediction_scores (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    market_id UUID NOT NULL REFERENCES markets(id) ON DELETE CASCADE,
+    category_id UUID REFERENCES categories(id),
+    predicted_probability DECIMAL(10, 6) NOT NULL, -- last forecast before resolution
+    actual_outcome DECIMAL(10, 6) NOT NULL, -- 0 or 1
+    brier_score DECIMAL(10, 6) NOT NULL,
+    resolved_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    UNIQUE(user_id, market_id)
+);
+
 -- Watchlists
 CREATE TABLE watchlist_items (
     id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
@@ -207,12 +416,12 @@ CREATE TABLE alerts (
 );
 
 -- Insert default sources
-INSERT INTO sources (slug, name, source_type, api_base_url) VALUES
-    ('polymarket', 'Polymarket', 'prediction_market', 'https://clob.polymarket.com'),
-    ('kalshi', 'Kalshi', 'prediction_market', 'https://api.elections.kalshi.com'),
-    ('metaculus', 'Metaculus', 'forecast_platform', 'https://www.metaculus.com/api2'),
-    ('manifold', 'Manifold Markets', 'prediction_market', 'https://api.manifold.markets/v0'),
-    ('predictit', 'PredictIt', 'prediction_market', 'https://www.predictit.org/api');
+INSERT INTO sources (slug, name, source_type, api_base_url, play_money, fee_model, currency) VALUES
+    ('polymarket', 'Polymarket', 'prediction_market', 'https://clob.polymarket.com', false, '{"name": "none"}', 'USDC'),
+    ('kalshi', 'Kalshi', 'prediction_market', 'https://api.elections.kalshi.com', false, '{"name": "kalshi_taker", "rate": 0.07}', 'USD'),
+    ('metaculus', 'Metaculus', 'forecast_platform', 'https://www.metaculus.com/api2', false, NULL, 'USD'),
+    ('manifold', 'Manifold Markets', 'prediction_market', 'https://api.manifold.markets/v0', true, '{"name": "none"}', 'MANA'),
+    ('predictit', 'PredictIt', 'prediction_market', 'https://www.predictit.org/api', false, '{"name": "profit_share", "rate": 0.10, "withdrawal_rate": 0.05}', 'USD');
 
 -- Insert default categories
 INSERT INTO categories (slug, name, description, icon) VALUES
@@ -246,7 +455,11 @@ CREATE INDEX idx_source_markets_market ON source_markets(market_id);
 CREATE INDEX idx_source_markets_source ON source_markets(source_id);
 CREATE INDEX idx_markets_category ON markets(category_id);
 CREATE INDEX idx_markets_status ON markets(status);
-CREATE INDEX idx_prediction_scores_source ON prediction_scores(source_id);
+CREATE INDEX idx_prediction_scores_source ON prediction_scores(source_id, horizon);
+CREATE INDEX idx_user_forecasts_user_market ON user_forecasts(user_id, market_id, created_at DESC);
+CREATE INDEX idx_user_prediction_scores_user ON user_prediction_scores(user_id);
 CREATE INDEX idx_movement_events_market ON movement_events(market_id, detected_at DESC);
+CREATE UNIQUE INDEX idx_arbitrage_open ON arbitrage_opportunities(market_id, yes_source_market_id, no_source_market_id) WHERE closed_at IS NULL;
+CREATE INDEX idx_arbitrage_detected ON arbitrage_opportunities(detected_at DESC);
 CREATE INDEX idx_whale_trades_wallet ON whale_trades(wallet_address, traded_at DESC);
 CREATE INDEX idx_whale_trades_market ON whale_trades(source_market_id, traded_at DESC);